@@ -0,0 +1,49 @@
+use glam::Vec2;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Aabb2 {
+    pub min: Vec2,
+    pub max: Vec2,
+}
+impl Aabb2 {
+    pub fn new(min: Vec2, max: Vec2) -> Self {
+        Self { min, max }
+    }
+    /// An inverted, empty box if there are no points.
+    pub fn from_points(points: impl IntoIterator<Item = Vec2>) -> Self {
+        points.into_iter().fold(
+            Self::new(Vec2::splat(f32::INFINITY), Vec2::splat(f32::NEG_INFINITY)),
+            |aabb, pt| Self::new(aabb.min.min(pt), aabb.max.max(pt)),
+        )
+    }
+    pub fn from_center_half_extents(center: Vec2, half_extents: Vec2) -> Self {
+        Self::new(center - half_extents, center + half_extents)
+    }
+
+    pub fn size(&self) -> Vec2 {
+        self.max - self.min
+    }
+    pub fn center(&self) -> Vec2 {
+        (self.min + self.max) * 0.5
+    }
+    pub fn half_extents(&self) -> Vec2 {
+        self.size() * 0.5
+    }
+
+    pub fn translate(&self, offset: Vec2) -> Self {
+        Self::new(self.min + offset, self.max + offset)
+    }
+
+    /// Uses half-open bounds, so boxes tiling the plane never share a point.
+    /// See `contains_inclusive` for closed bounds.
+    pub fn contains(&self, pt: Vec2) -> bool {
+        pt.cmpge(self.min).all() && pt.cmplt(self.max).all()
+    }
+    /// Points on any edge count as inside, as for `Shape2::contains`.
+    pub fn contains_inclusive(&self, pt: Vec2) -> bool {
+        pt.cmpge(self.min).all() && pt.cmple(self.max).all()
+    }
+    pub fn intersects(&self, that: &Self) -> bool {
+        self.min.cmplt(that.max).all() && that.min.cmplt(self.max).all()
+    }
+}
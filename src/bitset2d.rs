@@ -1,7 +1,14 @@
-use core::ops::Range;
+use core::{
+    fmt,
+    hash::{Hash, Hasher},
+    ops::Range,
+};
 
 use alloc::vec::Vec;
 
+/// Equality, hashing and `Debug` only consider the set cells, not how much
+/// space has been allocated for them.
+#[derive(Clone)]
 pub struct BitSet2d {
     bits: Vec<u64>,
     x_range: Range<isize>,
@@ -41,6 +48,51 @@ impl BitSet2d {
         get_bit(&self.bits[index], bit)
     }
 
+    /// A rect containing every set cell; it may be larger than the tightest one.
+    pub(crate) fn bounds(&self) -> (Range<isize>, Range<isize>) {
+        (self.x_range.clone(), self.y_range.clone())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (isize, isize)> + '_ {
+        self.chunks()
+            .flat_map(|((x, y), chunk)| set_bits(chunk).map(move |bit| (x + bit as isize, y)))
+    }
+
+    /// Non-empty chunks in row-major order, with the position of their first bit.
+    ///
+    /// Chunks are aligned to multiples of 64 regardless of the allocated
+    /// bounds, so this sequence only depends on which cells are set.
+    fn chunks(&self) -> impl Iterator<Item = ((isize, isize), u64)> + '_ {
+        let row_span = self.x_range.len() >> 6;
+        self.bits
+            .iter()
+            .enumerate()
+            .filter(|(_, chunk)| **chunk != 0)
+            .map(move |(index, &chunk)| {
+                let x = self.x_range.start + ((index % row_span) << 6) as isize;
+                let y = self.y_range.start + (index / row_span) as isize;
+                ((x, y), chunk)
+            })
+    }
+
+    /// The 8x8 block of cells starting at `(bx * 8, by * 8)`, packed row by
+    /// row with bit `row * 8 + column`.
+    pub(crate) fn block8x8(&self, (bx, by): (isize, isize)) -> u64 {
+        let (x, y) = (bx << 3, by << 3);
+        // Chunks are 64 aligned, so an 8 aligned row is either fully covered or not at all.
+        if !self.x_range.contains(&x) {
+            return 0;
+        }
+        let mut block = 0;
+        for row in 0..8 {
+            if self.y_range.contains(&(y + row)) {
+                let (index, bit) = self.index((x, y + row));
+                block |= ((self.bits[index] >> bit) & 0xff) << (row * 8);
+            }
+        }
+        block
+    }
+
     fn cover(&mut self, (x, y): (isize, isize)) {
         if self.bits.is_empty() {
             self.x_range = x_chunk_cover(x);
@@ -52,7 +104,7 @@ impl BitSet2d {
                 end: x_max,
             } = x_chunk_cover(x);
             let new_x_range = self.x_range.start.min(x_min)..self.x_range.end.max(x_max);
-            let new_y_range = self.y_range.start.min(y)..self.y_range.end.max(y);
+            let new_y_range = self.y_range.start.min(y)..self.y_range.end.max(y + 1);
 
             if new_x_range != self.x_range || new_y_range != self.y_range {
                 let new_x_chunks = (new_x_range.len() + 63) >> 6;
@@ -70,7 +122,7 @@ impl BitSet2d {
                     let old_x_iend = (new_x_range.start..self.x_range.end).len() >> 6;
 
                     let new_idx = new_iy * new_row_span;
-                    let old_idx = old_iy * old_row_span + old_x_istart;
+                    let old_idx = old_iy * old_row_span;
                     let len = (old_x_istart..old_x_iend).len();
 
                     self.bits[(new_idx + old_x_iend)..(new_idx + new_row_span)].fill(0);
@@ -104,6 +156,36 @@ impl BitSet2d {
     }
 }
 
+impl Default for BitSet2d {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PartialEq for BitSet2d {
+    fn eq(&self, other: &Self) -> bool {
+        self.chunks().eq(other.chunks())
+    }
+}
+impl Eq for BitSet2d {}
+
+impl Hash for BitSet2d {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let mut count = 0;
+        for chunk in self.chunks() {
+            chunk.hash(state);
+            count += 1;
+        }
+        state.write_usize(count);
+    }
+}
+
+impl fmt::Debug for BitSet2d {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
 fn x_chunk_cover(x: isize) -> Range<isize> {
     let min = if x >= 0 { x & !63 } else { (x | 63) - 63 };
     min..(min + 64)
@@ -113,6 +195,17 @@ fn get_bit(chunk: &u64, bit: u32) -> bool {
     ((*chunk >> bit) & 1) != 0
 }
 
+pub(crate) fn set_bits(mut chunk: u64) -> impl Iterator<Item = u32> {
+    core::iter::from_fn(move || {
+        if chunk == 0 {
+            return None;
+        }
+        let bit = chunk.trailing_zeros();
+        chunk &= chunk - 1;
+        Some(bit)
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use hashbrown::HashSet;
@@ -181,6 +274,20 @@ mod tests {
         assert_eq!(sut.contains((80, 2)), true);
     }
 
+    #[test]
+    fn test_insert_grow() {
+        let mut sut = BitSet2d::new();
+        let points = [(0, 0), (0, 1), (0, 2), (-1, 2), (-70, 1), (130, -3)];
+        for (i, p) in points.into_iter().enumerate() {
+            sut.insert(p);
+            for q in &points[..=i] {
+                assert!(sut.contains(*q), "p={q:?}");
+            }
+        }
+        assert!(!sut.contains((1, 2)));
+        assert!(!sut.contains((-70, 0)));
+    }
+
     #[test]
     fn test_remove() {
         let mut sut = BitSet2d::new();
@@ -189,6 +296,65 @@ mod tests {
         assert_eq!(sut.remove((0, 2)), false);
     }
 
+    #[test]
+    fn test_iter() {
+        let mut sut = BitSet2d::new();
+        assert_eq!(sut.iter().next(), None);
+
+        let points = [(-65, -1), (3, -1), (0, 2), (80, 2)];
+        for p in points {
+            sut.insert(p);
+        }
+        assert_eq!(sut.iter().collect::<Vec<_>>(), points);
+    }
+
+    #[test]
+    fn test_block8x8() {
+        let mut sut = BitSet2d::new();
+        sut.insert((8, 0));
+        sut.insert((15, 7));
+        sut.insert((-1, -1));
+        assert_eq!(sut.block8x8((1, 0)), 1 | (1 << 63));
+        assert_eq!(sut.block8x8((-1, -1)), 1 << 63);
+        assert_eq!(sut.block8x8((0, 0)), 0);
+        assert_eq!(sut.block8x8((100, 0)), 0);
+    }
+
+    #[test]
+    fn test_eq_ignores_bounds() {
+        use core::hash::BuildHasher;
+
+        let mut lhs = BitSet2d::new();
+        lhs.insert((1, 1));
+        lhs.insert((-100, 1));
+        lhs.insert((3, 50));
+        assert!(lhs.remove((-100, 1)));
+        assert!(lhs.remove((3, 50)));
+
+        let mut rhs = BitSet2d::new();
+        rhs.insert((1, 1));
+        assert_ne!(lhs.x_range, rhs.x_range);
+        assert_eq!(lhs, rhs);
+        assert_eq!(lhs.clone(), rhs);
+
+        let hasher = hashbrown::hash_map::DefaultHashBuilder::default();
+        assert_eq!(hasher.hash_one(&lhs), hasher.hash_one(&rhs));
+
+        rhs.insert((2, 1));
+        assert_ne!(lhs, rhs);
+        assert_ne!(BitSet2d::new(), rhs);
+        assert_eq!(BitSet2d::new(), BitSet2d::default());
+    }
+
+    #[test]
+    fn test_debug() {
+        let mut sut = BitSet2d::new();
+        assert_eq!(alloc::format!("{sut:?}"), "{}");
+        sut.insert((2, 1));
+        sut.insert((-1, 0));
+        assert_eq!(alloc::format!("{sut:?}"), "{(-1, 0), (2, 1)}");
+    }
+
     #[ignore = "expensive fuzzing, should be covered by other tests"]
     #[test]
     fn test_insert_fuzzing() {
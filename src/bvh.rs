@@ -0,0 +1,382 @@
+use alloc::{vec, vec::Vec};
+use core::ops::Range;
+
+use glam::Vec2;
+
+use crate::{aabb::Aabb2, segment::RayHit};
+
+/// A bounding volume hierarchy over the AABBs of many shapes, such as the
+/// triangles of a mesh, for finding which of them might touch a point, box or
+/// ray without checking every one.
+///
+/// Items are referred to by their index in the order they were given, and
+/// queries only test bounding boxes, so follow up with the exact test on each
+/// candidate, e.g. `Triangle2::contains`.
+#[derive(Clone, Debug)]
+pub struct Bvh {
+    nodes: Vec<Node>,
+    /// Item indices, grouped so each leaf covers a contiguous range.
+    order: Vec<usize>,
+    aabbs: Vec<Aabb2>,
+}
+
+#[derive(Clone, Debug)]
+struct Node {
+    aabb: Aabb2,
+    contents: Contents,
+}
+
+#[derive(Clone, Debug)]
+enum Contents {
+    Leaf(Range<usize>),
+    /// Children always come after their parent in `nodes`.
+    Branch([usize; 2]),
+}
+
+const LEAF_SIZE: usize = 4;
+
+impl Bvh {
+    /// Splits on the longest axis of the boxes' centres, at the median.
+    pub fn new(aabbs: impl IntoIterator<Item = Aabb2>) -> Self {
+        let aabbs: Vec<Aabb2> = aabbs.into_iter().collect();
+        let mut bvh = Self {
+            nodes: Vec::new(),
+            order: (0..aabbs.len()).collect(),
+            aabbs,
+        };
+        if !bvh.aabbs.is_empty() {
+            bvh.build(0..bvh.order.len());
+        }
+        bvh
+    }
+
+    pub fn len(&self) -> usize {
+        self.aabbs.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.aabbs.is_empty()
+    }
+    pub fn aabbs(&self) -> &[Aabb2] {
+        &self.aabbs
+    }
+
+    /// Updates the boxes after the shapes have moved, keeping the tree's
+    /// structure. `aabbs` must be in the same order as when built, and have
+    /// one box per item; otherwise returns `false` and leaves the tree as it
+    /// was.
+    ///
+    /// Queries stay correct however far things move, but get slower as the
+    /// tree drifts from a good fit, so rebuild after large changes.
+    pub fn refit(&mut self, aabbs: impl IntoIterator<Item = Aabb2>) -> bool {
+        let aabbs: Vec<Aabb2> = aabbs.into_iter().collect();
+        if aabbs.len() != self.aabbs.len() {
+            return false;
+        }
+        self.aabbs = aabbs;
+        for index in (0..self.nodes.len()).rev() {
+            self.nodes[index].aabb = match &self.nodes[index].contents {
+                Contents::Leaf(range) => self.bounds(range.clone()),
+                Contents::Branch([left, right]) => {
+                    union(&self.nodes[*left].aabb, &self.nodes[*right].aabb)
+                }
+            };
+        }
+        true
+    }
+
+    /// Items whose boxes contain `pt`, including on their edges.
+    pub fn query_point(&self, pt: Vec2) -> impl Iterator<Item = usize> + '_ {
+        self.query(move |aabb| pt.cmpge(aabb.min).all() && pt.cmple(aabb.max).all())
+    }
+
+    /// Items whose boxes overlap `area`, including boxes that only touch.
+    pub fn query_aabb(&self, area: Aabb2) -> impl Iterator<Item = usize> + '_ {
+        self.query(move |aabb| area.min.cmple(aabb.max).all() && aabb.min.cmple(area.max).all())
+    }
+
+    /// Items whose boxes the ray passes through, within `max_distance`
+    /// multiples of `direction`.
+    pub fn query_ray(
+        &self,
+        origin: Vec2,
+        direction: Vec2,
+        max_distance: f32,
+    ) -> impl Iterator<Item = usize> + '_ {
+        self.query(move |aabb| entry_distance(aabb, origin, direction, max_distance).is_some())
+    }
+
+    /// The nearest hit along the ray, visiting boxes closest first and
+    /// skipping any beyond the best hit so far. `raycast` is given an item
+    /// and the current maximum distance, for example
+    /// `|i, max| triangles[i].raycast(origin, direction, max)`.
+    pub fn raycast(
+        &self,
+        origin: Vec2,
+        direction: Vec2,
+        max_distance: f32,
+        mut raycast: impl FnMut(usize, f32) -> Option<RayHit>,
+    ) -> Option<(usize, RayHit)> {
+        let mut best: Option<(usize, RayHit)> = None;
+        let mut max_distance = max_distance;
+        let root = self.nodes.first()?;
+        let mut stack = vec![(
+            0,
+            entry_distance(&root.aabb, origin, direction, max_distance)?,
+        )];
+        while let Some((index, entry)) = stack.pop() {
+            if entry > max_distance {
+                continue;
+            }
+            match &self.nodes[index].contents {
+                Contents::Leaf(range) => {
+                    for &item in &self.order[range.clone()] {
+                        let hit =
+                            raycast(item, max_distance).filter(|hit| hit.distance <= max_distance);
+                        if let Some(hit) = hit {
+                            max_distance = hit.distance;
+                            best = Some((item, hit));
+                        }
+                    }
+                }
+                Contents::Branch(children) => {
+                    let mut entries = children.map(|child| {
+                        let aabb = &self.nodes[child].aabb;
+                        (child, entry_distance(aabb, origin, direction, max_distance))
+                    });
+                    // Push the further child first so the nearer pops next.
+                    if entries[0].1 < entries[1].1 {
+                        entries.swap(0, 1);
+                    }
+                    for (child, entry) in entries {
+                        if let Some(entry) = entry {
+                            stack.push((child, entry));
+                        }
+                    }
+                }
+            }
+        }
+        best
+    }
+
+    fn query<F: Fn(&Aabb2) -> bool>(&self, test: F) -> Query<'_, F> {
+        Query {
+            bvh: self,
+            test,
+            stack: if self.nodes.is_empty() {
+                vec![]
+            } else {
+                vec![0]
+            },
+            leaf: [].iter(),
+        }
+    }
+
+    /// Adds the node covering `order[range]`, returning its index.
+    fn build(&mut self, range: Range<usize>) -> usize {
+        let index = self.nodes.len();
+        let aabb = self.bounds(range.clone());
+        self.nodes.push(Node {
+            aabb,
+            contents: Contents::Leaf(range.clone()),
+        });
+        if range.len() <= LEAF_SIZE {
+            return index;
+        }
+
+        let centers = Aabb2::from_points(
+            self.order[range.clone()]
+                .iter()
+                .map(|&i| self.aabbs[i].center()),
+        );
+        let axis = if centers.size().x >= centers.size().y {
+            0
+        } else {
+            1
+        };
+        let aabbs = &self.aabbs;
+        let mid = range.start + range.len() / 2;
+        self.order[range.clone()].select_nth_unstable_by(mid - range.start, |&a, &b| {
+            aabbs[a].center()[axis].total_cmp(&aabbs[b].center()[axis])
+        });
+
+        let left = self.build(range.start..mid);
+        let right = self.build(mid..range.end);
+        self.nodes[index].contents = Contents::Branch([left, right]);
+        index
+    }
+
+    fn bounds(&self, range: Range<usize>) -> Aabb2 {
+        self.order[range]
+            .iter()
+            .fold(Aabb2::from_points([]), |acc, &i| {
+                union(&acc, &self.aabbs[i])
+            })
+    }
+}
+
+/// Depth-first traversal yielding items in leaves whose boxes pass `test`.
+struct Query<'a, F> {
+    bvh: &'a Bvh,
+    test: F,
+    stack: Vec<usize>,
+    leaf: core::slice::Iter<'a, usize>,
+}
+
+impl<F: Fn(&Aabb2) -> bool> Iterator for Query<'_, F> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        loop {
+            for &item in self.leaf.by_ref() {
+                if (self.test)(&self.bvh.aabbs[item]) {
+                    return Some(item);
+                }
+            }
+            let node = &self.bvh.nodes[self.stack.pop()?];
+            if !(self.test)(&node.aabb) {
+                continue;
+            }
+            match &node.contents {
+                Contents::Leaf(range) => self.leaf = self.bvh.order[range.clone()].iter(),
+                Contents::Branch([left, right]) => self.stack.extend([*right, *left]),
+            }
+        }
+    }
+}
+
+fn union(a: &Aabb2, b: &Aabb2) -> Aabb2 {
+    Aabb2::new(a.min.min(b.min), a.max.max(b.max))
+}
+
+/// How far along the ray it first touches `aabb`, or zero if it starts
+/// inside, using the slab method.
+fn entry_distance(aabb: &Aabb2, origin: Vec2, direction: Vec2, max_distance: f32) -> Option<f32> {
+    let (mut enter, mut exit) = (0.0f32, max_distance);
+    for axis in 0..2 {
+        let (o, d) = (origin[axis], direction[axis]);
+        let (min, max) = (aabb.min[axis], aabb.max[axis]);
+        if d == 0.0 {
+            if o < min || o > max {
+                return None;
+            }
+            continue;
+        }
+        let (t1, t2) = ((min - o) / d, (max - o) / d);
+        enter = enter.max(t1.min(t2));
+        exit = exit.min(t1.max(t2));
+        if enter > exit {
+            return None;
+        }
+    }
+    Some(enter)
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{Rng, SeedableRng};
+
+    use super::*;
+    use crate::{quadrilateral::Quad2, triangle::Triangle2};
+
+    fn random_triangles(rng: &mut impl Rng, count: usize) -> Vec<Triangle2> {
+        (0..count)
+            .map(|_| {
+                let corner = Vec2::new(rng.gen_range(0.0..100.0), rng.gen_range(0.0..100.0));
+                Triangle2(
+                    [0, 1, 2].map(|_| {
+                        corner + Vec2::new(rng.gen_range(0.0..5.0), rng.gen_range(0.0..5.0))
+                    }),
+                )
+            })
+            .collect()
+    }
+
+    fn sorted(items: impl Iterator<Item = usize>) -> Vec<usize> {
+        let mut items: Vec<usize> = items.collect();
+        items.sort_unstable();
+        items
+    }
+
+    #[test]
+    fn test_queries_match_brute_force() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+        let triangles = random_triangles(&mut rng, 500);
+        let sut = Bvh::new(triangles.iter().map(Triangle2::aabb));
+        assert_eq!(sut.len(), 500);
+
+        for _ in 0..100 {
+            let pt = Vec2::new(rng.gen_range(0.0..100.0), rng.gen_range(0.0..100.0));
+            let found = sorted(sut.query_point(pt).filter(|&i| triangles[i].contains(pt)));
+            let expected: Vec<usize> = (0..triangles.len())
+                .filter(|&i| triangles[i].contains(pt))
+                .collect();
+            assert_eq!(found, expected);
+
+            let area = Aabb2::from_center_half_extents(pt, Vec2::splat(3.0));
+            let expected: Vec<usize> = (0..triangles.len())
+                .filter(|&i| {
+                    let aabb = triangles[i].aabb();
+                    area.min.cmple(aabb.max).all() && aabb.min.cmple(area.max).all()
+                })
+                .collect();
+            assert_eq!(sorted(sut.query_aabb(area)), expected);
+
+            let direction = Vec2::from_angle(rng.gen_range(0.0..core::f32::consts::TAU));
+            let hit = sut.raycast(pt, direction, 50.0, |i, max| {
+                triangles[i].raycast(pt, direction, max)
+            });
+            let expected = (0..triangles.len())
+                .filter_map(|i| triangles[i].raycast(pt, direction, 50.0))
+                .map(|hit| hit.distance)
+                .min_by(f32::total_cmp);
+            assert_eq!(hit.map(|(_, hit)| hit.distance), expected);
+            if let Some((i, _)) = hit {
+                assert!(sut.query_ray(pt, direction, 50.0).any(|j| j == i));
+            }
+        }
+    }
+
+    #[test]
+    fn test_refit() {
+        let mut quads: Vec<Quad2> = (0..20)
+            .map(|i| {
+                let min = Vec2::new(i as f32 * 2.0, 0.0);
+                Quad2::from_aabb(&Aabb2::new(min, min + Vec2::ONE))
+            })
+            .collect();
+        let mut sut = Bvh::new(quads.iter().map(Quad2::aabb));
+        let pt = Vec2::new(4.5, 0.5);
+        assert_eq!(sorted(sut.query_point(pt)), [2]);
+
+        // Slide every quad up, and the last one onto `pt`.
+        for quad in &mut quads {
+            *quad = quad.translate(Vec2::Y * 10.0);
+        }
+        quads[19] = Quad2::from_aabb(&Aabb2::new(Vec2::new(4.0, 0.0), Vec2::new(5.0, 1.0)));
+        assert!(sut.refit(quads.iter().map(Quad2::aabb)));
+        assert_eq!(sorted(sut.query_point(pt)), [19]);
+        assert_eq!(sorted(sut.query_point(pt + Vec2::Y * 10.0)), [2]);
+        assert_eq!(
+            sut.raycast(Vec2::new(4.5, -5.0), Vec2::Y, 100.0, |i, max| quads[i]
+                .raycast(Vec2::new(4.5, -5.0), Vec2::Y, max))
+                .map(|(i, hit)| (i, hit.distance)),
+            Some((19, 5.0))
+        );
+
+        let empty = Bvh::new([]);
+        assert!(empty.is_empty());
+        assert_eq!(empty.query_point(pt).next(), None);
+        assert_eq!(empty.raycast(pt, Vec2::X, 1.0, |_, _| None), None);
+    }
+
+    #[test]
+    fn test_refit_wrong_count() {
+        let aabbs = [Aabb2::new(Vec2::ZERO, Vec2::ONE); 3];
+        let mut sut = Bvh::new(aabbs);
+        let moved = Aabb2::new(Vec2::splat(5.0), Vec2::splat(6.0));
+        assert!(!sut.refit([moved; 2]));
+        assert!(!sut.refit([moved; 4]));
+        assert_eq!(sut.aabbs(), aabbs);
+        assert_eq!(sorted(sut.query_point(Vec2::splat(0.5))), [0, 1, 2]);
+    }
+}
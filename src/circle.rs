@@ -0,0 +1,21 @@
+use glam::Vec2;
+
+use crate::aabb::Aabb2;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Circle {
+    pub center: Vec2,
+    pub radius: f32,
+}
+impl Circle {
+    pub fn new(center: Vec2, radius: f32) -> Self {
+        Self { center, radius }
+    }
+
+    pub fn contains(&self, pt: Vec2) -> bool {
+        pt.distance_squared(self.center) <= self.radius * self.radius
+    }
+    pub fn aabb(&self) -> Aabb2 {
+        Aabb2::from_center_half_extents(self.center, Vec2::splat(self.radius))
+    }
+}
@@ -0,0 +1,90 @@
+use alloc::vec::Vec;
+use glam::Vec2;
+
+use crate::{aabb::Aabb2, convex_polygon::ConvexPolygon2};
+
+/// Clips a polygon to `normal.dot(pt) >= offset`, one step of
+/// Sutherland–Hodgman.
+pub fn clip_to_half_plane(polygon: &[Vec2], normal: Vec2, offset: f32) -> Vec<Vec2> {
+    let mut clipped = Vec::with_capacity(polygon.len() + 1);
+    for (i, &a) in polygon.iter().enumerate() {
+        let b = polygon[(i + 1) % polygon.len()];
+        let (da, db) = (normal.dot(a) - offset, normal.dot(b) - offset);
+        if da >= 0.0 {
+            clipped.push(a);
+        }
+        if (da > 0.0 && db < 0.0) || (da < 0.0 && db > 0.0) {
+            clipped.push(a.lerp(b, da / (da - db)));
+        }
+    }
+    clipped
+}
+
+/// Sutherland–Hodgman clipping of any polygon to a convex one. Concave
+/// subjects that get split come back joined by zero-width seams along the
+/// clip edges.
+pub fn clip_polygon(subject: &[Vec2], clip: &ConvexPolygon2) -> Vec<Vec2> {
+    let mut clipped = subject.to_vec();
+    for edge in clip.edges() {
+        if clipped.is_empty() {
+            break;
+        }
+        // `ConvexPolygon2` winds positively, so the inside is to the left.
+        let normal = edge.direction().perp();
+        clipped = clip_to_half_plane(&clipped, normal, normal.dot(edge[0]));
+    }
+    clipped
+}
+
+pub fn clip_polygon_to_aabb(subject: &[Vec2], aabb: &Aabb2) -> Vec<Vec2> {
+    let planes = [
+        (Vec2::X, aabb.min.x),
+        (Vec2::Y, aabb.min.y),
+        (Vec2::NEG_X, -aabb.max.x),
+        (Vec2::NEG_Y, -aabb.max.y),
+    ];
+    let mut clipped = subject.to_vec();
+    for (normal, offset) in planes {
+        if clipped.is_empty() {
+            break;
+        }
+        clipped = clip_to_half_plane(&clipped, normal, offset);
+    }
+    clipped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clip_polygon() {
+        let diamond = [
+            Vec2::new(0.0, -1.5),
+            Vec2::new(1.5, 0.0),
+            Vec2::new(0.0, 1.5),
+            Vec2::new(-1.5, 0.0),
+        ];
+        let aabb = Aabb2::new(Vec2::splat(-1.0), Vec2::splat(1.0));
+        let clipped = clip_polygon_to_aabb(&diamond, &aabb);
+        assert_eq!(clipped.len(), 8);
+        let octagon = ConvexPolygon2::new(clipped).unwrap();
+        assert!((octagon.area() - (4.0 - 4.0 * 0.125)).abs() < 1e-5);
+
+        let square = ConvexPolygon2::convex_hull([
+            Vec2::splat(-1.0),
+            Vec2::new(1.0, -1.0),
+            Vec2::splat(1.0),
+            Vec2::new(-1.0, 1.0),
+        ])
+        .unwrap();
+        let clipped = clip_polygon(&diamond, &square);
+        assert_eq!(clipped.len(), octagon.vertices().len());
+        for (a, b) in clipped.iter().zip(octagon.vertices()) {
+            assert!(a.abs_diff_eq(*b, 1e-5), "{a} {b}");
+        }
+
+        let far = Aabb2::new(Vec2::splat(5.0), Vec2::splat(6.0));
+        assert!(clip_polygon_to_aabb(&diamond, &far).is_empty());
+    }
+}
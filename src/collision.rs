@@ -0,0 +1,718 @@
+use alloc::vec::Vec;
+use glam::Vec2;
+use libm::sqrtf;
+
+use crate::{
+    aabb::Aabb2,
+    circle::Circle,
+    convex_polygon::ConvexPolygon2,
+    quadrilateral::{Quad2, QuadKind},
+    segment::Segment2,
+    shape::Shape2,
+    triangle::Triangle2,
+};
+
+const MAX_ITERATIONS: usize = 64;
+const EPA_TOLERANCE: f32 = 1e-4;
+
+/// How two overlapping shapes `a` and `b` penetrate each other.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Manifold {
+    /// Unit direction from `a` towards `b`; moving `b` by `normal * depth`
+    /// separates them.
+    pub normal: Vec2,
+    pub depth: f32,
+    points: [Vec2; 2],
+    point_count: usize,
+}
+impl Manifold {
+    fn new(normal: Vec2, depth: f32, points: &[Vec2]) -> Self {
+        let mut manifold = Self {
+            normal,
+            depth,
+            points: [Vec2::ZERO; 2],
+            point_count: points.len().min(2),
+        };
+        manifold.points[..manifold.point_count].copy_from_slice(&points[..manifold.point_count]);
+        manifold
+    }
+
+    /// One or two contact points, halfway between the two surfaces.
+    pub fn points(&self) -> &[Vec2] {
+        &self.points[..self.point_count]
+    }
+
+    /// The same contact, seen from `b`.
+    pub fn flipped(&self) -> Self {
+        Self {
+            normal: -self.normal,
+            ..*self
+        }
+    }
+}
+
+/// When two moving shapes first touch.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TimeOfImpact {
+    /// As a fraction of the motion, from `0.0` to `1.0`.
+    pub time: f32,
+    /// Unit direction from `a` towards `b` where they touch, or zero if they
+    /// already overlap at the start.
+    pub normal: Vec2,
+}
+
+/// The shapes with dedicated overlap tests, from SAT for polygons and
+/// closest points for circles.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Collider {
+    Circle(Circle),
+    Aabb(Aabb2),
+    Triangle(Triangle2),
+    /// Concave and bow-tie quads are treated as their two triangles.
+    Quad(Quad2),
+}
+impl Collider {
+    pub fn overlaps(&self, other: &Collider) -> bool {
+        self.manifold(other).is_some()
+    }
+
+    /// `None` if the shapes don't overlap. Shapes that only touch give a
+    /// manifold with zero depth.
+    pub fn manifold(&self, other: &Collider) -> Option<Manifold> {
+        let mut deepest: Option<Manifold> = None;
+        for a in self.parts().iter().flatten() {
+            for b in other.parts().iter().flatten() {
+                let manifold = match (a, b) {
+                    (Part::Circle(a), Part::Circle(b)) => circle_circle(a, b),
+                    (Part::Circle(a), Part::Polygon(b, len)) => {
+                        circle_polygon(a, &b[..*len]).map(|m| m.flipped())
+                    }
+                    (Part::Polygon(a, len), Part::Circle(b)) => circle_polygon(b, &a[..*len]),
+                    (Part::Polygon(a, a_len), Part::Polygon(b, b_len)) => {
+                        sat_polygons(&a[..*a_len], &b[..*b_len])
+                    }
+                };
+                if let Some(manifold) = manifold {
+                    if !deepest.is_some_and(|deepest| deepest.depth >= manifold.depth) {
+                        deepest = Some(manifold);
+                    }
+                }
+            }
+        }
+        deepest
+    }
+
+    /// The first time `self` moving by `motion` touches `other` moving by
+    /// `other_motion`, with both moving in a straight line without rotating.
+    /// Unlike checking for overlap after each step, fast shapes can't pass
+    /// through thin ones.
+    pub fn time_of_impact(
+        &self,
+        motion: Vec2,
+        other: &Collider,
+        other_motion: Vec2,
+    ) -> Option<TimeOfImpact> {
+        // Equivalent to `other` moving relative to `self`, which is a ray
+        // from the origin against the Minkowski difference `self - other`.
+        let direction = other_motion - motion;
+        let mut earliest: Option<TimeOfImpact> = None;
+        for a in self.parts().iter().flatten() {
+            for b in other.parts().iter().flatten() {
+                let ((a_points, a_radius), (b_points, b_radius)) = (a.rounded(), b.rounded());
+                let difference = a_points
+                    .iter()
+                    .flat_map(|&pa| b_points.iter().map(move |&pb| pa - pb));
+                let impact = sweep_rounded(&hull(difference), a_radius + b_radius, direction);
+                if let Some(impact) = impact {
+                    if !earliest.is_some_and(|earliest| earliest.time <= impact.time) {
+                        earliest = Some(impact);
+                    }
+                }
+            }
+        }
+        earliest
+    }
+
+    /// `time_of_impact` against a shape that isn't moving.
+    pub fn sweep(&self, motion: Vec2, other: &Collider) -> Option<TimeOfImpact> {
+        self.time_of_impact(motion, other, Vec2::ZERO)
+    }
+
+    fn parts(&self) -> [Option<Part>; 2] {
+        match *self {
+            Collider::Circle(circle) => [Some(Part::Circle(circle)), None],
+            Collider::Aabb(Aabb2 { min, max }) => [
+                Some(Part::Polygon(
+                    [min, Vec2::new(max.x, min.y), max, Vec2::new(min.x, max.y)],
+                    4,
+                )),
+                None,
+            ],
+            Collider::Triangle(triangle) => [Some(Part::triangle(&triangle)), None],
+            Collider::Quad(quad) if quad.kind() == QuadKind::Convex => {
+                [Some(Part::Polygon(quad.0, 4)), None]
+            }
+            Collider::Quad(quad) => quad.triangles().map(|t| Some(Part::triangle(&t))),
+        }
+    }
+}
+
+impl From<Circle> for Collider {
+    fn from(circle: Circle) -> Self {
+        Collider::Circle(circle)
+    }
+}
+impl From<Aabb2> for Collider {
+    fn from(aabb: Aabb2) -> Self {
+        Collider::Aabb(aabb)
+    }
+}
+impl From<Triangle2> for Collider {
+    fn from(triangle: Triangle2) -> Self {
+        Collider::Triangle(triangle)
+    }
+}
+impl From<Quad2> for Collider {
+    fn from(quad: Quad2) -> Self {
+        Collider::Quad(quad)
+    }
+}
+
+/// A convex piece of a collider.
+enum Part {
+    Circle(Circle),
+    Polygon([Vec2; 4], usize),
+}
+impl Part {
+    fn triangle(triangle: &Triangle2) -> Self {
+        let [v1, v2, v3] = triangle.0;
+        Part::Polygon([v1, v2, v3, v3], 3)
+    }
+
+    /// The part as the points of a polygon inflated by a radius.
+    fn rounded(&self) -> (&[Vec2], f32) {
+        match self {
+            Part::Circle(circle) => (core::slice::from_ref(&circle.center), circle.radius),
+            Part::Polygon(points, len) => (&points[..*len], 0.0),
+        }
+    }
+}
+
+pub fn circle_circle(a: &Circle, b: &Circle) -> Option<Manifold> {
+    let offset = b.center - a.center;
+    let distance = offset.length();
+    let depth = a.radius + b.radius - distance;
+    if depth < 0.0 {
+        return None;
+    }
+    let normal = offset.try_normalize().unwrap_or(Vec2::X);
+    let point = a.center + normal * (a.radius - 0.5 * depth);
+    Some(Manifold::new(normal, depth, &[point]))
+}
+
+/// The manifold from a convex `polygon`, in either winding, to the circle.
+pub fn circle_polygon(circle: &Circle, polygon: &[Vec2]) -> Option<Manifold> {
+    let sign = winding_sign(polygon);
+    let (edge, separation) = (0..polygon.len())
+        .map(|i| {
+            let normal = edge_normal(polygon, i, sign);
+            (i, normal.dot(circle.center - polygon[i]))
+        })
+        .max_by(|a, b| a.1.total_cmp(&b.1))?;
+    if separation > circle.radius {
+        return None;
+    }
+
+    let (normal, depth) = if separation <= 0.0 {
+        // The centre is inside, so push out through the nearest face.
+        (edge_normal(polygon, edge, sign), circle.radius - separation)
+    } else {
+        let closest = (0..polygon.len())
+            .map(|i| {
+                let segment = Segment2([polygon[i], polygon[(i + 1) % polygon.len()]]);
+                segment.closest_point(circle.center)
+            })
+            .min_by(|a, b| {
+                a.distance_squared(circle.center)
+                    .total_cmp(&b.distance_squared(circle.center))
+            })?;
+        let offset = circle.center - closest;
+        let distance = offset.length();
+        if distance > circle.radius {
+            return None;
+        }
+        (offset / distance, circle.radius - distance)
+    };
+    let point = circle.center - normal * (circle.radius - 0.5 * depth);
+    Some(Manifold::new(normal, depth, &[point]))
+}
+
+/// The separating axis test between two convex polygons, in either winding,
+/// clipping the incident edge against the reference edge for up to two
+/// contact points.
+pub fn sat_polygons(a: &[Vec2], b: &[Vec2]) -> Option<Manifold> {
+    let (a_sign, b_sign) = (winding_sign(a), winding_sign(b));
+    let (a_edge, a_separation) = max_separation(a, a_sign, b)?;
+    let (b_edge, b_separation) = max_separation(b, b_sign, a)?;
+    if a_separation > 0.0 || b_separation > 0.0 {
+        return None;
+    }
+
+    // Prefer `a` as the reference unless `b` is clearly better, for stability.
+    let flip = b_separation > a_separation + 1e-3 * a_separation.abs().max(1e-3);
+    let (reference, reference_sign, edge, incident, incident_sign) = if flip {
+        (b, b_sign, b_edge, a, a_sign)
+    } else {
+        (a, a_sign, a_edge, b, b_sign)
+    };
+
+    let normal = edge_normal(reference, edge, reference_sign);
+    let incident_edge = (0..incident.len())
+        .min_by(|&i, &j| {
+            let di = edge_normal(incident, i, incident_sign).dot(normal);
+            let dj = edge_normal(incident, j, incident_sign).dot(normal);
+            di.total_cmp(&dj)
+        })
+        .unwrap_or(0);
+    let mut points = [
+        incident[incident_edge],
+        incident[(incident_edge + 1) % incident.len()],
+    ];
+
+    let r1 = reference[edge];
+    let r2 = reference[(edge + 1) % reference.len()];
+    let tangent = (r2 - r1).normalize_or_zero();
+    if !clip(&mut points, tangent, tangent.dot(r1))
+        || !clip(&mut points, -tangent, -tangent.dot(r2))
+    {
+        return None;
+    }
+
+    let mut contacts = [Vec2::ZERO; 2];
+    let mut count = 0;
+    let mut depth: f32 = 0.0;
+    for pt in points {
+        let separation = normal.dot(pt - r1);
+        if separation <= 0.0 {
+            contacts[count] = pt - normal * (0.5 * separation);
+            count += 1;
+            depth = depth.max(-separation);
+        }
+    }
+    let normal = if flip { -normal } else { normal };
+    Some(Manifold::new(normal, depth, &contacts[..count]))
+}
+
+/// Whether the convex hulls of the shapes overlap, using GJK.
+pub fn gjk_overlaps<A, B>(a: &A, b: &B) -> bool
+where
+    A: Shape2 + ?Sized,
+    B: Shape2 + ?Sized,
+{
+    gjk(a, b).is_some()
+}
+
+/// GJK followed by EPA, for any pair of shapes with a support function. Gives
+/// a single contact point, and treats concave shapes as their convex hulls.
+pub fn gjk_epa<A, B>(a: &A, b: &B) -> Option<Manifold>
+where
+    A: Shape2 + ?Sized,
+    B: Shape2 + ?Sized,
+{
+    let simplex = gjk(a, b)?;
+    let support = |d: Vec2| a.support(d) - b.support(-d);
+
+    let mut polytope: Vec<Vec2> = simplex.into();
+    if winding_sign(&polytope) < 0.0 {
+        polytope.reverse();
+    }
+    let mut best = (Vec2::X, f32::INFINITY);
+    for _ in 0..MAX_ITERATIONS {
+        let closest = (0..polytope.len())
+            .filter_map(|i| {
+                let normal = edge_normal(&polytope, i, 1.0);
+                (normal != Vec2::ZERO).then(|| (i, normal, normal.dot(polytope[i])))
+            })
+            .min_by(|a, b| a.2.total_cmp(&b.2));
+        let Some((edge, normal, distance)) = closest else {
+            break;
+        };
+        best = (normal, distance);
+
+        let pt = support(normal);
+        if pt.dot(normal) - distance <= EPA_TOLERANCE * distance.max(1.0) {
+            break;
+        }
+        polytope.insert(edge + 1, pt);
+    }
+
+    let (normal, depth) = best;
+    if !depth.is_finite() {
+        return None;
+    }
+    let depth = depth.max(0.0);
+    let point = a.support(normal) - normal * (0.5 * depth);
+    Some(Manifold::new(normal, depth, &[point]))
+}
+
+/// A triangle of the Minkowski difference `a - b` containing the origin.
+fn gjk<A, B>(a: &A, b: &B) -> Option<[Vec2; 3]>
+where
+    A: Shape2 + ?Sized,
+    B: Shape2 + ?Sized,
+{
+    let support = |d: Vec2| a.support(d) - b.support(-d);
+
+    let mut direction = b.aabb().center() - a.aabb().center();
+    if direction == Vec2::ZERO {
+        direction = Vec2::X;
+    }
+    let mut simplex = [support(direction); 3];
+    let mut len = 1;
+    direction = -simplex[0];
+
+    for _ in 0..MAX_ITERATIONS {
+        if direction == Vec2::ZERO {
+            // The origin is on the simplex, so the shapes are touching.
+            direction = match len {
+                1 => Vec2::X,
+                _ => (simplex[1] - simplex[0]).perp(),
+            };
+        }
+        let pt = support(direction);
+        if pt.dot(direction) < 0.0 {
+            return None;
+        }
+        simplex[len] = pt;
+        len += 1;
+
+        let newest = simplex[len - 1];
+        let to_origin = -newest;
+        if len == 2 {
+            let ab = simplex[0] - newest;
+            if ab.dot(to_origin) > 0.0 {
+                direction = towards(ab.perp(), to_origin);
+            } else {
+                simplex[0] = newest;
+                len = 1;
+                direction = to_origin;
+            }
+        } else {
+            let (b, c) = (simplex[1], simplex[0]);
+            let (ab, ac) = (b - newest, c - newest);
+            let ab_normal = away_from(ab.perp(), ac);
+            let ac_normal = away_from(ac.perp(), ab);
+            if ab_normal.dot(to_origin) > 0.0 {
+                simplex = [b, newest, newest];
+                len = 2;
+                direction = ab_normal;
+            } else if ac_normal.dot(to_origin) > 0.0 {
+                simplex = [c, newest, newest];
+                len = 2;
+                direction = ac_normal;
+            } else {
+                return Some([c, b, newest]);
+            }
+        }
+    }
+    None
+}
+
+/// The convex hull in `Orientation::Positive` winding, or for collinear
+/// points, the two ends of the segment through them.
+fn hull(points: impl Iterator<Item = Vec2> + Clone) -> Vec<Vec2> {
+    if let Some(hull) = ConvexPolygon2::convex_hull(points.clone()) {
+        return hull.into_vertices();
+    }
+    let order = |a: &Vec2, b: &Vec2| a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y));
+    let ends = [points.clone().min_by(order), points.max_by(order)];
+    let mut hull: Vec<Vec2> = ends.into_iter().flatten().collect();
+    hull.dedup();
+    hull
+}
+
+/// Casts a ray from the origin along `direction`, up to one multiple of it,
+/// against `hull` inflated by `radius`.
+fn sweep_rounded(hull: &[Vec2], radius: f32, direction: Vec2) -> Option<TimeOfImpact> {
+    if distance_to_hull(hull, Vec2::ZERO) <= radius {
+        return Some(TimeOfImpact {
+            time: 0.0,
+            normal: Vec2::ZERO,
+        });
+    }
+
+    // The ray starts outside, so the first boundary piece it crosses is where
+    // it enters.
+    let faces = (0..hull.len()).filter_map(|i| {
+        let normal = edge_normal(hull, i, 1.0);
+        if normal.dot(direction) >= 0.0 {
+            return None;
+        }
+        let offset = normal * radius;
+        let face = Segment2([hull[i] + offset, hull[(i + 1) % hull.len()] + offset]);
+        let time = face.raycast(Vec2::ZERO, direction, 1.0)?;
+        Some(TimeOfImpact { time, normal })
+    });
+    let corners = hull.iter().filter(|_| radius > 0.0).filter_map(|&corner| {
+        // Solve `|direction * time - corner| = radius` for the first root.
+        let a = direction.length_squared();
+        let b = direction.dot(corner);
+        let discriminant = b * b - a * (corner.length_squared() - radius * radius);
+        if a == 0.0 || discriminant < 0.0 {
+            return None;
+        }
+        let time = (b - sqrtf(discriminant)) / a;
+        (0.0..=1.0).contains(&time).then(|| TimeOfImpact {
+            time,
+            normal: (direction * time - corner).normalize_or_zero(),
+        })
+    });
+    faces
+        .chain(corners)
+        .min_by(|a, b| a.time.total_cmp(&b.time))
+}
+
+/// Zero inside a hull in `Orientation::Positive` winding.
+fn distance_to_hull(hull: &[Vec2], pt: Vec2) -> f32 {
+    let len = hull.len();
+    let inside = len >= 3 && (0..len).all(|i| edge_normal(hull, i, 1.0).dot(pt - hull[i]) <= 0.0);
+    if inside {
+        return 0.0;
+    }
+    (0..len)
+        .map(|i| Segment2([hull[i], hull[(i + 1) % len]]).distance(pt))
+        .fold(f32::INFINITY, f32::min)
+}
+
+fn towards(v: Vec2, target: Vec2) -> Vec2 {
+    if v.dot(target) < 0.0 {
+        -v
+    } else {
+        v
+    }
+}
+fn away_from(v: Vec2, target: Vec2) -> Vec2 {
+    -towards(v, target)
+}
+
+/// `1.0` for `Orientation::Positive` winding, otherwise `-1.0`.
+fn winding_sign(polygon: &[Vec2]) -> f32 {
+    let len = polygon.len();
+    let area: f32 = (0..len)
+        .map(|i| polygon[i].perp_dot(polygon[(i + 1) % len]))
+        .sum();
+    if area < 0.0 {
+        -1.0
+    } else {
+        1.0
+    }
+}
+
+/// The outward unit normal of edge `i`.
+fn edge_normal(polygon: &[Vec2], i: usize, sign: f32) -> Vec2 {
+    let edge = polygon[(i + 1) % polygon.len()] - polygon[i];
+    (Vec2::new(edge.y, -edge.x) * sign).normalize_or_zero()
+}
+
+/// The edge of `polygon` that `other` is furthest outside of, and how far.
+fn max_separation(polygon: &[Vec2], sign: f32, other: &[Vec2]) -> Option<(usize, f32)> {
+    (0..polygon.len())
+        .map(|i| {
+            let normal = edge_normal(polygon, i, sign);
+            let separation = other
+                .iter()
+                .map(|v| normal.dot(*v - polygon[i]))
+                .fold(f32::INFINITY, f32::min);
+            (i, separation)
+        })
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+}
+
+/// Clips the segment to `normal.dot(pt) >= offset`, returning false if nothing
+/// is left.
+fn clip(points: &mut [Vec2; 2], normal: Vec2, offset: f32) -> bool {
+    let d = points.map(|pt| normal.dot(pt) - offset);
+    match (d[0] >= 0.0, d[1] >= 0.0) {
+        (true, true) => true,
+        (false, false) => false,
+        (inside_first, _) => {
+            let crossing = points[0].lerp(points[1], d[0] / (d[0] - d[1]));
+            if inside_first {
+                points[1] = crossing;
+            } else {
+                points[0] = crossing;
+            }
+            true
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{Rng, SeedableRng};
+
+    use super::*;
+
+    fn square(center: Vec2, half: f32) -> Collider {
+        Aabb2::from_center_half_extents(center, Vec2::splat(half)).into()
+    }
+
+    #[test]
+    fn test_boxes() {
+        let a = square(Vec2::ZERO, 1.0);
+        let b = square(Vec2::new(1.5, 0.5), 1.0);
+        let manifold = a.manifold(&b).unwrap();
+        assert_eq!(manifold.normal, Vec2::X);
+        assert_eq!(manifold.depth, 0.5);
+        assert_eq!(
+            manifold.points(),
+            [Vec2::new(0.75, 1.0), Vec2::new(0.75, -0.5)]
+        );
+
+        let flipped = b.manifold(&a).unwrap();
+        assert_eq!(flipped.normal, Vec2::NEG_X);
+        assert_eq!(flipped.depth, 0.5);
+
+        assert!(!a.overlaps(&square(Vec2::new(2.5, 0.0), 1.0)));
+        assert_eq!(
+            a.manifold(&square(Vec2::new(2.0, 0.0), 1.0)).unwrap().depth,
+            0.0
+        );
+    }
+
+    #[test]
+    fn test_circles() {
+        let a: Collider = Circle::new(Vec2::ZERO, 1.0).into();
+        let b: Collider = Circle::new(Vec2::new(0.0, 1.5), 1.0).into();
+        let manifold = a.manifold(&b).unwrap();
+        assert_eq!(manifold.normal, Vec2::Y);
+        assert_eq!(manifold.depth, 0.5);
+        assert_eq!(manifold.points(), [Vec2::new(0.0, 0.75)]);
+
+        let boxed = square(Vec2::new(1.5, 0.0), 1.0);
+        let manifold = a.manifold(&boxed).unwrap();
+        assert_eq!(manifold.normal, Vec2::X);
+        assert_eq!(manifold.depth, 0.5);
+        let manifold = boxed.manifold(&a).unwrap();
+        assert_eq!(manifold.normal, Vec2::NEG_X);
+
+        let corner = square(Vec2::new(1.5, 1.5), 1.0);
+        let manifold = a.manifold(&corner).unwrap();
+        assert!(manifold.normal.abs_diff_eq(Vec2::ONE.normalize(), 1e-6));
+        assert!(!a.overlaps(&square(Vec2::new(1.8, 1.8), 1.0)));
+    }
+
+    #[test]
+    fn test_concave_quad() {
+        let arrow: Collider = Quad2([
+            Vec2::new(0.0, 0.0),
+            Vec2::new(2.0, 1.0),
+            Vec2::new(4.0, 0.0),
+            Vec2::new(2.0, 4.0),
+        ])
+        .into();
+        // Sits in the notch, inside the convex hull but not the quad.
+        let notch: Collider = Circle::new(Vec2::new(2.0, 0.3), 0.2).into();
+        assert!(!arrow.overlaps(&notch));
+        assert!(arrow.overlaps(&Collider::Circle(Circle::new(Vec2::new(2.0, 0.3), 0.8))));
+    }
+
+    #[test]
+    fn test_gjk_epa_matches_sat() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+        for _ in 0..500 {
+            let mut triangle = || {
+                let center = Vec2::new(rng.gen_range(-2.0..2.0), rng.gen_range(-2.0..2.0));
+                Triangle2([0, 1, 2].map(|i| {
+                    let angle = i as f32 * 2.1 + rng.gen_range(0.0..0.5);
+                    center + Vec2::from_angle(angle) * rng.gen_range(0.5..2.0)
+                }))
+            };
+            let (a, b) = (triangle(), triangle());
+
+            let sat = Collider::Triangle(a).manifold(&Collider::Triangle(b));
+            let epa = gjk_epa(&a, &b);
+            assert_eq!(sat.is_some(), gjk_overlaps(&a, &b), "{a:?} {b:?}");
+            if let (Some(sat), Some(epa)) = (sat, epa) {
+                assert!((sat.depth - epa.depth).abs() < 1e-3, "{sat:?} {epa:?}");
+                if sat.depth > 1e-3 {
+                    assert!(sat.normal.abs_diff_eq(epa.normal, 1e-2), "{sat:?} {epa:?}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_time_of_impact() {
+        // Too fast to be caught by overlap tests at either end of the step.
+        let bullet = square(Vec2::new(-10.0, 0.0), 0.1);
+        let wall: Collider =
+            Quad2::from_aabb(&Aabb2::new(Vec2::new(0.0, -5.0), Vec2::new(0.05, 5.0))).into();
+        let motion = Vec2::new(20.0, 0.0);
+        assert!(!bullet.overlaps(&wall));
+        let impact = bullet.sweep(motion, &wall).unwrap();
+        assert!((impact.time - 0.495).abs() < 1e-6, "{impact:?}");
+        assert_eq!(impact.normal, Vec2::X);
+        assert_eq!(bullet.sweep(-motion, &wall), None);
+        assert_eq!(bullet.sweep(Vec2::new(20.0, 12.0), &wall), None);
+
+        let a: Collider = Circle::new(Vec2::ZERO, 1.0).into();
+        let b: Collider = Circle::new(Vec2::new(10.0, 0.0), 1.0).into();
+        let impact = a
+            .time_of_impact(Vec2::new(10.0, 0.0), &b, Vec2::new(-10.0, 0.0))
+            .unwrap();
+        assert!((impact.time - 0.4).abs() < 1e-6, "{impact:?}");
+        assert!(impact.normal.abs_diff_eq(Vec2::X, 1e-6));
+
+        // Hitting a corner pushes out diagonally.
+        let ball: Collider = Circle::new(Vec2::splat(-3.0), 1.0).into();
+        let impact = ball
+            .sweep(Vec2::splat(4.0), &square(Vec2::ZERO, 1.0))
+            .unwrap();
+        let expected = (2.0 - core::f32::consts::FRAC_1_SQRT_2) / 4.0;
+        assert!((impact.time - expected).abs() < 1e-5, "{impact:?}");
+        assert!(impact.normal.abs_diff_eq(Vec2::ONE.normalize(), 1e-5));
+
+        assert_eq!(
+            a.sweep(Vec2::X, &square(Vec2::new(1.5, 0.0), 1.0)),
+            Some(TimeOfImpact {
+                time: 0.0,
+                normal: Vec2::ZERO
+            })
+        );
+    }
+
+    #[test]
+    fn test_time_of_impact_matches_stepping() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(1);
+        for _ in 0..200 {
+            let mut triangle = || {
+                let center = Vec2::new(rng.gen_range(-5.0..5.0), rng.gen_range(-5.0..5.0));
+                Triangle2([0, 1, 2].map(|i| {
+                    let angle = i as f32 * 2.1 + rng.gen_range(0.0..0.5);
+                    center + Vec2::from_angle(angle) * rng.gen_range(0.5..2.0)
+                }))
+            };
+            let (a, b) = (triangle(), triangle());
+            let mut motion = || Vec2::new(rng.gen_range(-8.0..8.0), rng.gen_range(-8.0..8.0));
+            let (a_motion, b_motion) = (motion(), motion());
+            let at = |time: f32| {
+                let a = Collider::Triangle(a.translate(a_motion * time));
+                a.overlaps(&Collider::Triangle(b.translate(b_motion * time)))
+            };
+
+            let impact = Collider::Triangle(a).time_of_impact(a_motion, &b.into(), b_motion);
+            let stepped = (0..=1000).map(|i| i as f32 / 1000.0).find(|&time| at(time));
+            match (impact, stepped) {
+                (Some(impact), Some(time)) => {
+                    assert!(impact.time <= time + 1e-4, "{impact:?} {time}");
+                    assert!(at(impact.time + 1e-3), "{impact:?}");
+                }
+                (None, None) => {}
+                // Only grazing contacts fall between the steps.
+                (Some(impact), None) => assert!(at(impact.time), "{impact:?}"),
+                (None, Some(time)) => panic!("missed contact at {time}"),
+            }
+        }
+    }
+}
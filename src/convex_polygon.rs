@@ -0,0 +1,419 @@
+use alloc::{vec, vec::Vec};
+use glam::Vec2;
+use libm::atan2f;
+
+use crate::{
+    aabb::Aabb2,
+    clipping::{clip_polygon, clip_to_half_plane},
+    offset::{offset_ring, Join},
+    predicates::{orient2d, Orientation},
+    segment::Segment2,
+    triangle::Triangle2,
+};
+
+/// A convex polygon, stored with `Orientation::Positive` winding.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConvexPolygon2 {
+    vertices: Vec<Vec2>,
+}
+impl ConvexPolygon2 {
+    /// `None` unless the distinct vertices form a convex polygon with some
+    /// area, in either winding. Straight corners are allowed, and repeated
+    /// consecutive vertices are merged.
+    pub fn new(mut vertices: Vec<Vec2>) -> Option<Self> {
+        vertices.dedup();
+        if vertices.len() > 1 && vertices.first() == vertices.last() {
+            vertices.pop();
+        }
+        let len = vertices.len();
+        if len < 3 {
+            return None;
+        }
+        let mut winding = Orientation::Collinear;
+        for i in 0..len {
+            let turn = orient2d(
+                vertices[i],
+                vertices[(i + 1) % len],
+                vertices[(i + 2) % len],
+            );
+            match (winding, turn) {
+                (_, Orientation::Collinear) => {}
+                (Orientation::Collinear, turn) => winding = turn,
+                (winding, turn) if winding != turn => return None,
+                _ => {}
+            }
+        }
+        match winding {
+            Orientation::Collinear => return None,
+            Orientation::Negative => vertices.reverse(),
+            Orientation::Positive => {}
+        }
+        let polygon = Self { vertices };
+        // Turning the same way at every corner still allows winding around twice.
+        (polygon.total_turning() < 3.0 * core::f32::consts::PI).then_some(polygon)
+    }
+
+    /// The smallest convex polygon containing all the points, or `None` if
+    /// they are all collinear.
+    pub fn convex_hull(points: impl IntoIterator<Item = Vec2>) -> Option<Self> {
+        let mut points: Vec<Vec2> = points.into_iter().collect();
+        points.sort_by(|a, b| a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y)));
+        points.dedup();
+
+        // Andrew's monotone chain, with each half excluding its last point.
+        let mut hull = half_hull(points.iter().copied());
+        hull.extend(half_hull(points.iter().rev().copied()));
+        Self::new(hull)
+    }
+
+    pub fn vertices(&self) -> &[Vec2] {
+        &self.vertices
+    }
+    pub fn into_vertices(self) -> Vec<Vec2> {
+        self.vertices
+    }
+
+    /// The edges, with edge `i` running from vertex `i` to vertex `i + 1`.
+    pub fn edges(&self) -> impl Iterator<Item = Segment2> + '_ {
+        let len = self.vertices.len();
+        (0..len).map(move |i| Segment2([self.vertices[i], self.vertices[(i + 1) % len]]))
+    }
+
+    /// Points on the edges count as inside.
+    pub fn contains(&self, pt: Vec2) -> bool {
+        self.edges()
+            .all(|Segment2([a, b])| orient2d(a, b, pt) != Orientation::Negative)
+    }
+    pub fn area(&self) -> f32 {
+        0.5 * self.signed_area_x2()
+    }
+    pub fn aabb(&self) -> Aabb2 {
+        Aabb2::from_points(self.vertices.iter().copied())
+    }
+    pub fn centroid(&self) -> Vec2 {
+        let origin = self.vertices[0];
+        let (mut weighted, mut total) = (Vec2::ZERO, 0.0);
+        for Segment2([a, b]) in self.edges() {
+            let area = (a - origin).perp_dot(b - origin);
+            weighted += (origin + a + b) * area;
+            total += area;
+        }
+        weighted / (3.0 * total)
+    }
+    pub fn support(&self, direction: Vec2) -> Vec2 {
+        support_of(&self.vertices, direction)
+    }
+
+    /// A fan from the first vertex.
+    pub fn triangles(&self) -> impl Iterator<Item = Triangle2> + '_ {
+        let first = self.vertices[0];
+        self.vertices[1..]
+            .windows(2)
+            .map(move |pair| Triangle2([first, pair[0], pair[1]]))
+    }
+
+    /// The overlap, or `None` if it has no area.
+    pub fn intersection(&self, other: &ConvexPolygon2) -> Option<ConvexPolygon2> {
+        ConvexPolygon2::convex_hull(clip_polygon(&self.vertices, other))
+    }
+    /// The part of `self` outside `other`, as disjoint convex pieces, one for
+    /// each edge of `other` that `self` crosses.
+    pub fn difference(&self, other: &ConvexPolygon2) -> Vec<ConvexPolygon2> {
+        let mut pieces = Vec::new();
+        let mut remaining = self.vertices.clone();
+        for edge in other.edges() {
+            if remaining.is_empty() {
+                break;
+            }
+            let inward = edge.direction().perp();
+            let offset = inward.dot(edge[0]);
+            pieces.extend(ConvexPolygon2::convex_hull(clip_to_half_plane(
+                &remaining, -inward, -offset,
+            )));
+            remaining = clip_to_half_plane(&remaining, inward, offset);
+        }
+        pieces
+    }
+    /// The combined area as disjoint convex pieces, starting with `self`.
+    pub fn union(&self, other: &ConvexPolygon2) -> Vec<ConvexPolygon2> {
+        let mut pieces = vec![self.clone()];
+        pieces.extend(other.difference(self));
+        pieces
+    }
+
+    /// Moves every edge outwards by `distance`, or inwards if it's negative,
+    /// filling the gaps opened at the corners with `join`. `None` if it
+    /// shrinks away to nothing.
+    pub fn offset(&self, distance: f32, join: Join) -> Option<ConvexPolygon2> {
+        if distance >= 0.0 {
+            return ConvexPolygon2::convex_hull(offset_ring(&self.vertices, distance, join)?);
+        }
+        // Going inwards never opens gaps, and clipping handles edges that
+        // shrink away.
+        let mut shrunk = self.vertices.clone();
+        for edge in self.edges() {
+            let inward = edge.direction().perp().normalize_or_zero();
+            shrunk = clip_to_half_plane(&shrunk, inward, inward.dot(edge[0]) - distance);
+        }
+        ConvexPolygon2::convex_hull(shrunk)
+    }
+
+    /// Every sum of a point in `self` and a point in `other`, found by merging
+    /// their edges in order of angle.
+    pub fn minkowski_sum(&self, other: &ConvexPolygon2) -> ConvexPolygon2 {
+        let (a, b) = (self.starting_lowest(), other.starting_lowest());
+        let (n, m) = (a.len(), b.len());
+        let mut vertices = Vec::with_capacity(n + m);
+        let (mut i, mut j) = (0, 0);
+        while i < n || j < m {
+            vertices.push(a[i % n] + b[j % m]);
+            let turn = (a[(i + 1) % n] - a[i % n]).perp_dot(b[(j + 1) % m] - b[j % m]);
+            if j == m || (i < n && turn > 0.0) {
+                i += 1;
+            } else if i == n || turn < 0.0 {
+                j += 1;
+            } else {
+                i += 1;
+                j += 1;
+            }
+        }
+        ConvexPolygon2 { vertices }
+    }
+    /// The Minkowski sum with `other` mirrored through the origin. This is
+    /// where `other`'s origin can be for the two to overlap, its
+    /// configuration-space obstacle.
+    pub fn minkowski_difference(&self, other: &ConvexPolygon2) -> ConvexPolygon2 {
+        let mirrored = ConvexPolygon2 {
+            vertices: other.vertices.iter().map(|&v| -v).collect(),
+        };
+        self.minkowski_sum(&mirrored)
+    }
+
+    /// The vertices starting from the lowest, leftmost one.
+    fn starting_lowest(&self) -> Vec<Vec2> {
+        let lowest = (0..self.vertices.len())
+            .min_by(|&i, &j| {
+                let (a, b) = (self.vertices[i], self.vertices[j]);
+                a.y.total_cmp(&b.y).then(a.x.total_cmp(&b.x))
+            })
+            .unwrap_or(0);
+        let mut vertices = self.vertices.clone();
+        vertices.rotate_left(lowest);
+        vertices
+    }
+
+    fn signed_area_x2(&self) -> f32 {
+        self.edges().map(|Segment2([a, b])| a.perp_dot(b)).sum()
+    }
+    /// `2π` for simple polygons.
+    fn total_turning(&self) -> f32 {
+        let edges: Vec<Vec2> = self.edges().map(|edge| edge.direction()).collect();
+        (0..edges.len())
+            .map(|i| {
+                let next = edges[(i + 1) % edges.len()];
+                atan2f(edges[i].perp_dot(next), edges[i].dot(next))
+            })
+            .sum()
+    }
+}
+
+fn half_hull(points: impl Iterator<Item = Vec2>) -> Vec<Vec2> {
+    let mut hull: Vec<Vec2> = Vec::new();
+    for pt in points {
+        while hull.len() >= 2
+            && orient2d(hull[hull.len() - 2], hull[hull.len() - 1], pt) != Orientation::Positive
+        {
+            hull.pop();
+        }
+        hull.push(pt);
+    }
+    hull.pop();
+    hull
+}
+
+pub(crate) fn support_of(vertices: &[Vec2], direction: Vec2) -> Vec2 {
+    vertices
+        .iter()
+        .copied()
+        .max_by(|a, b| a.dot(direction).total_cmp(&b.dot(direction)))
+        .unwrap_or(Vec2::ZERO)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new() {
+        let square = vec![Vec2::ZERO, Vec2::Y, Vec2::ONE, Vec2::X];
+        let sut = ConvexPolygon2::new(square).unwrap();
+        assert_eq!(sut.vertices(), [Vec2::X, Vec2::ONE, Vec2::Y, Vec2::ZERO]);
+        assert_eq!(sut.area(), 1.0);
+        assert_eq!(sut.centroid(), Vec2::splat(0.5));
+        assert!(sut.contains(Vec2::new(1.0, 0.5)));
+        assert!(!sut.contains(Vec2::new(1.1, 0.5)));
+
+        let repeated = vec![Vec2::ZERO, Vec2::X, Vec2::X, Vec2::ONE, Vec2::Y, Vec2::ZERO];
+        assert_eq!(ConvexPolygon2::new(repeated).unwrap().vertices().len(), 4);
+
+        let concave = vec![
+            Vec2::ZERO,
+            Vec2::new(2.0, 0.0),
+            Vec2::splat(0.5),
+            Vec2::new(0.0, 2.0),
+        ];
+        assert_eq!(ConvexPolygon2::new(concave), None);
+        let pentagram: Vec<_> = (0..5)
+            .map(|i| Vec2::from_angle(i as f32 * 4.0 * core::f32::consts::PI / 5.0))
+            .collect();
+        assert_eq!(ConvexPolygon2::new(pentagram), None);
+        assert_eq!(
+            ConvexPolygon2::new(vec![Vec2::ZERO, Vec2::ONE, Vec2::splat(2.0)]),
+            None
+        );
+    }
+
+    #[test]
+    fn test_convex_hull() {
+        let points = [
+            Vec2::new(0.0, 0.0),
+            Vec2::new(1.0, 1.0),
+            Vec2::new(2.0, 0.0),
+            Vec2::new(2.0, 2.0),
+            Vec2::new(1.0, 0.0),
+            Vec2::new(0.0, 2.0),
+            Vec2::new(0.5, 1.5),
+        ];
+        let sut = ConvexPolygon2::convex_hull(points).unwrap();
+        assert_eq!(
+            sut.vertices(),
+            [
+                Vec2::new(0.0, 0.0),
+                Vec2::new(2.0, 0.0),
+                Vec2::new(2.0, 2.0),
+                Vec2::new(0.0, 2.0)
+            ]
+        );
+        assert_eq!(sut.support(Vec2::new(1.0, 0.1)), Vec2::new(2.0, 2.0));
+        assert_eq!(ConvexPolygon2::convex_hull([Vec2::ZERO, Vec2::ONE]), None);
+    }
+
+    #[test]
+    fn test_booleans() {
+        let square = |min: Vec2, size: f32| {
+            ConvexPolygon2::convex_hull([
+                min,
+                min + Vec2::X * size,
+                min + Vec2::splat(size),
+                min + Vec2::Y * size,
+            ])
+            .unwrap()
+        };
+        let a = square(Vec2::ZERO, 2.0);
+        let b = square(Vec2::ONE, 2.0);
+
+        assert!((a.intersection(&b).unwrap().area() - 1.0).abs() < 1e-5);
+        assert_eq!(a.intersection(&square(Vec2::splat(3.0), 1.0)), None);
+
+        let difference = a.difference(&b);
+        let area: f32 = difference.iter().map(ConvexPolygon2::area).sum();
+        assert_eq!(area, 3.0);
+        assert!(difference
+            .iter()
+            .all(|piece| !piece.contains(Vec2::splat(1.5))));
+
+        let union = a.union(&b);
+        let area: f32 = union.iter().map(ConvexPolygon2::area).sum();
+        assert_eq!(area, 7.0);
+
+        // Cutting a hole leaves a ring of pieces around it.
+        let ring = a.difference(&square(Vec2::splat(0.5), 1.0));
+        assert_eq!(ring.len(), 4);
+        let area: f32 = ring.iter().map(ConvexPolygon2::area).sum();
+        assert_eq!(area, 3.0);
+        assert_eq!(a.triangles().count(), 2);
+    }
+
+    #[test]
+    fn test_offset() {
+        let square = ConvexPolygon2::new(vec![
+            Vec2::ZERO,
+            Vec2::new(2.0, 0.0),
+            Vec2::splat(2.0),
+            Vec2::new(0.0, 2.0),
+        ])
+        .unwrap();
+
+        let mitred = square.offset(1.0, Join::Miter { limit: 2.0 }).unwrap();
+        assert!((mitred.area() - 16.0).abs() < 1e-5);
+        assert!(mitred.contains(Vec2::splat(-1.0)));
+        let bevelled = square.offset(1.0, Join::Miter { limit: 1.0 }).unwrap();
+        assert!((bevelled.area() - 14.0).abs() < 1e-5);
+        let rounded = square.offset(1.0, Join::Round { tolerance: 1e-3 }).unwrap();
+        let expected = 12.0 + core::f32::consts::PI;
+        assert!(
+            (rounded.area() - expected).abs() < 1e-2,
+            "{}",
+            rounded.area()
+        );
+        for &v in rounded.vertices() {
+            let distance = square
+                .edges()
+                .map(|edge| edge.distance(v))
+                .fold(f32::MAX, f32::min);
+            assert!((distance - 1.0).abs() < 1e-3, "{v}");
+        }
+
+        let shrunk = square
+            .offset(-0.5, Join::Round { tolerance: 1e-3 })
+            .unwrap();
+        assert!((shrunk.area() - 1.0).abs() < 1e-5);
+        assert!(shrunk.contains(Vec2::splat(1.5)));
+        assert_eq!(square.offset(-1.5, Join::Miter { limit: 2.0 }), None);
+
+        let triangle = Triangle2([Vec2::ZERO, Vec2::new(4.0, 0.0), Vec2::new(0.0, 3.0)]);
+        let grown = triangle.offset(0.5, Join::Miter { limit: 4.0 }).unwrap();
+        assert_eq!(grown.vertices().len(), 3);
+        // Perimeter 12, so the incircle's radius is 1 and shrinking scales.
+        let shrunk = triangle.offset(-0.5, Join::Miter { limit: 4.0 }).unwrap();
+        assert!((shrunk.area() - 1.5).abs() < 1e-5, "{}", shrunk.area());
+    }
+
+    #[test]
+    fn test_minkowski() {
+        let square = ConvexPolygon2::new(vec![Vec2::ZERO, Vec2::X, Vec2::ONE, Vec2::Y]).unwrap();
+        let triangle =
+            ConvexPolygon2::new(vec![Vec2::ZERO, Vec2::new(2.0, 0.0), Vec2::new(1.0, 3.0)])
+                .unwrap();
+        let sum = square.minkowski_sum(&triangle);
+        let brute = ConvexPolygon2::convex_hull(
+            square
+                .vertices()
+                .iter()
+                .flat_map(|&a| triangle.vertices().iter().map(move |&b| a + b)),
+        )
+        .unwrap();
+        assert!((sum.area() - brute.area()).abs() < 1e-5);
+        assert!(ConvexPolygon2::new(sum.vertices().to_vec()).is_some());
+        // Parallel edges merge, leaving a straight corner where they meet.
+        assert!((square.minkowski_sum(&square).area() - 4.0).abs() < 1e-5);
+
+        // The triangle overlaps the square exactly when moved into the difference.
+        let obstacle = square.minkowski_difference(&triangle);
+        for (offset, overlaps) in [
+            (Vec2::new(-1.5, -1.0), true),
+            (Vec2::new(-2.5, 0.0), false),
+            (Vec2::new(0.5, 0.5), true),
+            (Vec2::new(1.5, 0.0), false),
+        ] {
+            let moved =
+                ConvexPolygon2::new(triangle.vertices().iter().map(|&v| v + offset).collect())
+                    .unwrap();
+            assert_eq!(
+                obstacle.contains(offset),
+                square.intersection(&moved).is_some()
+            );
+            assert_eq!(obstacle.contains(offset), overlaps, "{offset}");
+        }
+    }
+}
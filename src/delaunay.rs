@@ -0,0 +1,560 @@
+use alloc::{vec, vec::Vec};
+use glam::Vec2;
+
+use crate::{
+    aabb::Aabb2,
+    clipping::clip_to_half_plane,
+    convex_polygon::ConvexPolygon2,
+    predicates::{incircle, on_segment, orient2d, InCircle, Orientation},
+    triangle::Triangle2,
+};
+
+/// A Delaunay triangulation, built by inserting points one at a time
+/// (Bowyer–Watson). Edges on the convex hull are closed off by triangles to a
+/// vertex at infinity rather than a large enclosing triangle, so no hull
+/// triangles are lost however flat the hull is.
+///
+/// Triangles have `Orientation::Positive` winding, and neighbour `i` of a
+/// triangle is across the edge opposite its vertex `i`. Repeated points are
+/// only kept once, and collinear point sets have no triangles.
+#[derive(Clone, Debug)]
+pub struct Delaunay2 {
+    points: Vec<Vec2>,
+    triangles: Vec<[usize; 3]>,
+    neighbours: Vec<[Option<usize>; 3]>,
+}
+
+impl Delaunay2 {
+    pub fn new(points: impl IntoIterator<Item = Vec2>) -> Self {
+        let points = distinct(points.into_iter().collect());
+        let Some(seed) = first_triangle(&points) else {
+            return Self {
+                points,
+                triangles: Vec::new(),
+                neighbours: Vec::new(),
+            };
+        };
+        let mut builder = Builder::new(points, seed);
+        for index in 0..builder.points.len() {
+            if !seed.contains(&index) {
+                builder.insert(index);
+            }
+        }
+        builder.finish()
+    }
+
+    /// The distinct input points, in the order they were first given.
+    pub fn points(&self) -> &[Vec2] {
+        &self.points
+    }
+    /// Each triangle as indices into `points`.
+    pub fn indices(&self) -> &[[usize; 3]] {
+        &self.triangles
+    }
+    pub fn neighbours(&self) -> &[[Option<usize>; 3]] {
+        &self.neighbours
+    }
+    pub fn len(&self) -> usize {
+        self.triangles.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.triangles.is_empty()
+    }
+
+    pub fn triangle(&self, index: usize) -> Triangle2 {
+        Triangle2(self.triangles[index].map(|i| self.points[i]))
+    }
+    pub fn triangles(&self) -> impl Iterator<Item = Triangle2> + '_ {
+        (0..self.triangles.len()).map(|index| self.triangle(index))
+    }
+
+    /// The Voronoi cell of each point, in the same order as `points`, clipped
+    /// to `bounds`. Cells entirely outside `bounds` are `None`.
+    pub fn voronoi_cells(&self, bounds: &Aabb2) -> Vec<Option<ConvexPolygon2>> {
+        let mut adjacent = vec![Vec::new(); self.points.len()];
+        for &[a, b, c] in &self.triangles {
+            for (from, to) in [(a, b), (b, c), (c, a)] {
+                adjacent[from].push(to);
+                adjacent[to].push(from);
+            }
+        }
+        if self.triangles.is_empty() {
+            // Collinear points only neighbour the next along their line.
+            for pair in sorted_order(&self.points).windows(2) {
+                adjacent[pair[0]].push(pair[1]);
+                adjacent[pair[1]].push(pair[0]);
+            }
+        }
+
+        let corners = [
+            bounds.min,
+            Vec2::new(bounds.max.x, bounds.min.y),
+            bounds.max,
+            Vec2::new(bounds.min.x, bounds.max.y),
+        ];
+        adjacent
+            .into_iter()
+            .enumerate()
+            .map(|(index, mut adjacent)| {
+                adjacent.sort_unstable();
+                adjacent.dedup();
+                let site = self.points[index];
+                let mut cell = corners.to_vec();
+                for other in adjacent {
+                    // Keep the side of the bisector closer to `site`.
+                    let other = self.points[other];
+                    let normal = site - other;
+                    cell = clip_to_half_plane(&cell, normal, normal.dot(0.5 * (site + other)));
+                }
+                ConvexPolygon2::convex_hull(cell)
+            })
+            .collect()
+    }
+}
+
+/// Indices of `points` sorted by x then y, which for collinear points is
+/// their order along the line.
+fn sorted_order(points: &[Vec2]) -> Vec<usize> {
+    // Adding zero turns `-0.0` into `0.0`, so equal points sort together.
+    let key = |pt: Vec2| (pt.x + 0.0, pt.y + 0.0);
+    let mut order: Vec<usize> = (0..points.len()).collect();
+    order.sort_by(|&i, &j| {
+        let ((ix, iy), (jx, jy)) = (key(points[i]), key(points[j]));
+        ix.total_cmp(&jx).then(iy.total_cmp(&jy)).then(i.cmp(&j))
+    });
+    order
+}
+
+/// `points` without repeats, keeping the first of each in order.
+fn distinct(points: Vec<Vec2>) -> Vec<Vec2> {
+    let order = sorted_order(&points);
+    let mut keep = vec![true; points.len()];
+    for pair in order.windows(2) {
+        if points[pair[0]] == points[pair[1]] {
+            keep[pair[1]] = false;
+        }
+    }
+    points
+        .into_iter()
+        .zip(keep)
+        .filter_map(|(pt, keep)| keep.then_some(pt))
+        .collect()
+}
+
+/// The first two distinct points and the first point off the line through
+/// them, with `Orientation::Positive` winding. `None` if all are collinear.
+fn first_triangle(points: &[Vec2]) -> Option<[usize; 3]> {
+    let (a, b) = (*points.first()?, *points.get(1)?);
+    let (index, turn) = points
+        .iter()
+        .enumerate()
+        .skip(2)
+        .map(|(index, &pt)| (index, orient2d(a, b, pt)))
+        .find(|&(_, turn)| turn != Orientation::Collinear)?;
+    Some(if turn == Orientation::Positive {
+        [0, 1, index]
+    } else {
+        [1, 0, index]
+    })
+}
+
+/// Stands in for the vertex at infinity in `Builder::triangles`.
+const INFINITE: usize = usize::MAX;
+
+/// Triangulation state while inserting. Triangles using the `INFINITE` vertex
+/// lie outside the hull, one beyond each hull edge, so every finite triangle
+/// has all three neighbours.
+struct Builder {
+    points: Vec<Vec2>,
+    triangles: Vec<[usize; 3]>,
+    neighbours: Vec<[Option<usize>; 3]>,
+    alive: Vec<bool>,
+    /// Where to start walking from, as consecutive points tend to be close.
+    last: usize,
+}
+
+impl Builder {
+    fn new(points: Vec<Vec2>, [a, b, c]: [usize; 3]) -> Self {
+        // The seed triangle, then one infinite triangle beyond each edge, each
+        // sharing its other two edges with the others.
+        Self {
+            points,
+            triangles: vec![
+                [a, b, c],
+                [b, a, INFINITE],
+                [c, b, INFINITE],
+                [a, c, INFINITE],
+            ],
+            neighbours: vec![
+                [Some(2), Some(3), Some(1)],
+                [Some(3), Some(2), Some(0)],
+                [Some(1), Some(3), Some(0)],
+                [Some(2), Some(1), Some(0)],
+            ],
+            alive: vec![true; 4],
+            last: 0,
+        }
+    }
+
+    fn insert(&mut self, index: usize) {
+        let pt = self.points[index];
+        let start = self.locate(pt);
+
+        // Flood out from the containing triangle through every triangle whose
+        // circumcircle contains the point.
+        let mut cavity = vec![start];
+        self.alive[start] = false;
+        let mut boundary = Vec::new();
+        let mut next = 0;
+        while next < cavity.len() {
+            let triangle = cavity[next];
+            next += 1;
+            let vertices = self.triangles[triangle];
+            for edge in 0..3 {
+                let neighbour = self.neighbours[triangle][edge];
+                let inside = neighbour.is_some_and(|n| self.alive[n] && self.encloses(n, pt));
+                match neighbour {
+                    Some(n) if inside => {
+                        self.alive[n] = false;
+                        cavity.push(n);
+                    }
+                    Some(n) if !self.alive[n] => {}
+                    _ => boundary.push((
+                        vertices[(edge + 1) % 3],
+                        vertices[(edge + 2) % 3],
+                        neighbour,
+                        triangle,
+                    )),
+                }
+            }
+        }
+
+        // Fan the cavity's boundary edges to the new point.
+        let first = self.triangles.len();
+        for &(a, b, outside, old) in &boundary {
+            let triangle = self.triangles.len();
+            self.triangles.push([a, b, index]);
+            self.neighbours.push([None, None, outside]);
+            self.alive.push(true);
+            if let Some(outside) = outside {
+                for slot in &mut self.neighbours[outside] {
+                    if *slot == Some(old) {
+                        *slot = Some(triangle);
+                    }
+                }
+            }
+        }
+        for triangle in first..self.triangles.len() {
+            let [a, b, _] = self.triangles[triangle];
+            let starting_at = |vertex: usize| {
+                (first..self.triangles.len()).find(|&t| self.triangles[t][0] == vertex)
+            };
+            let ending_at = |vertex: usize| {
+                (first..self.triangles.len()).find(|&t| self.triangles[t][1] == vertex)
+            };
+            self.neighbours[triangle][0] = starting_at(b);
+            self.neighbours[triangle][1] = ending_at(a);
+        }
+        self.last = first;
+    }
+
+    /// For infinite triangles, the hull edge they lie beyond, in the order
+    /// that has them on its left.
+    fn hull_edge(&self, triangle: usize) -> Option<(usize, usize)> {
+        let vertices = self.triangles[triangle];
+        let i = vertices.iter().position(|&v| v == INFINITE)?;
+        Some((vertices[(i + 1) % 3], vertices[(i + 2) % 3]))
+    }
+
+    /// Whether the triangle's circumcircle strictly contains `pt`. For
+    /// infinite triangles that's the open half-plane beyond their hull edge,
+    /// plus the inside of the edge itself.
+    fn encloses(&self, triangle: usize, pt: Vec2) -> bool {
+        if let Some((a, b)) = self.hull_edge(triangle) {
+            let (a, b) = (self.points[a], self.points[b]);
+            return match orient2d(a, b, pt) {
+                Orientation::Positive => true,
+                Orientation::Collinear => on_segment(pt, a, b),
+                Orientation::Negative => false,
+            };
+        }
+        let [a, b, c] = self.triangles[triangle].map(|i| self.points[i]);
+        incircle(a, b, c, pt) == InCircle::Inside
+    }
+
+    /// A live triangle whose circumcircle contains `pt`, found by walking
+    /// towards it. That's the triangle containing it, or an infinite triangle
+    /// if it's outside the hull.
+    fn locate(&self, pt: Vec2) -> usize {
+        let mut triangle = self.last;
+        // Walks like this always terminate in Delaunay triangulations.
+        'walk: loop {
+            let vertices = self.triangles[triangle];
+            if let Some((a, b)) = self.hull_edge(triangle) {
+                if orient2d(self.points[a], self.points[b], pt) == Orientation::Positive {
+                    return triangle;
+                }
+                let inward = vertices.iter().position(|&v| v == INFINITE);
+                match inward.and_then(|edge| self.neighbours[triangle][edge]) {
+                    Some(neighbour) => triangle = neighbour,
+                    None => return triangle,
+                }
+                continue;
+            }
+            for edge in 0..3 {
+                let (a, b) = (vertices[(edge + 1) % 3], vertices[(edge + 2) % 3]);
+                if orient2d(self.points[a], self.points[b], pt) == Orientation::Negative {
+                    if let Some(neighbour) = self.neighbours[triangle][edge] {
+                        triangle = neighbour;
+                        continue 'walk;
+                    }
+                }
+            }
+            return triangle;
+        }
+    }
+
+    fn finish(self) -> Delaunay2 {
+        let mut remap = vec![None; self.triangles.len()];
+        let mut kept = 0;
+        for (triangle, vertices) in self.triangles.iter().enumerate() {
+            if self.alive[triangle] && !vertices.contains(&INFINITE) {
+                remap[triangle] = Some(kept);
+                kept += 1;
+            }
+        }
+
+        let mut triangles = Vec::with_capacity(kept);
+        let mut neighbours = Vec::with_capacity(kept);
+        for (triangle, vertices) in self.triangles.iter().enumerate() {
+            if remap[triangle].is_some() {
+                triangles.push(*vertices);
+                neighbours.push(self.neighbours[triangle].map(|n| n.and_then(|n| remap[n])));
+            }
+        }
+        Delaunay2 {
+            points: self.points,
+            triangles,
+            neighbours,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{Rng, SeedableRng};
+
+    use super::*;
+    use crate::segment::Segment2;
+
+    fn random_points(rng: &mut impl Rng, count: usize) -> Vec<Vec2> {
+        (0..count)
+            .map(|_| Vec2::new(rng.gen_range(0.0..100.0), rng.gen_range(0.0..100.0)))
+            .collect()
+    }
+
+    #[test]
+    fn test_empty_circumcircles() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+        let points = random_points(&mut rng, 200);
+        let sut = Delaunay2::new(points.iter().copied());
+        assert_eq!(sut.points(), points);
+
+        for triangle in sut.triangles() {
+            assert_eq!(triangle.orientation(), Orientation::Positive);
+            let [a, b, c] = triangle.0;
+            for &pt in &points {
+                assert_ne!(incircle(a, b, c, pt), InCircle::Inside);
+            }
+        }
+
+        // Covers the convex hull exactly.
+        let hull = ConvexPolygon2::convex_hull(points.iter().copied()).unwrap();
+        let area: f32 = sut.triangles().map(|triangle| triangle.area()).sum();
+        assert!((area - hull.area()).abs() < 1e-2, "{area} {}", hull.area());
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(1);
+        let sut = Delaunay2::new(random_points(&mut rng, 100));
+        for (triangle, neighbours) in sut.neighbours().iter().enumerate() {
+            let vertices = sut.indices()[triangle];
+            for (edge, neighbour) in neighbours.iter().enumerate() {
+                let (a, b) = (vertices[(edge + 1) % 3], vertices[(edge + 2) % 3]);
+                match neighbour {
+                    Some(n) => {
+                        let other = sut.indices()[*n];
+                        let slot = sut.neighbours()[*n]
+                            .iter()
+                            .position(|&t| t == Some(triangle));
+                        let slot = slot.unwrap();
+                        assert_eq!((other[(slot + 1) % 3], other[(slot + 2) % 3]), (b, a));
+                    }
+                    // Only hull edges have no neighbour, so nothing lies beyond them.
+                    None => assert!(sut.points().iter().all(|&pt| orient2d(
+                        sut.points()[a],
+                        sut.points()[b],
+                        pt
+                    ) != Orientation::Negative)),
+                }
+            }
+        }
+    }
+
+    /// Every point is used, and the triangles exactly fill the hull, with one
+    /// hull edge for each point on the hull's boundary.
+    fn assert_complete(sut: &Delaunay2) {
+        let points = sut.points();
+        let mut used = vec![false; points.len()];
+        for vertices in sut.indices() {
+            for &i in vertices {
+                used[i] = true;
+            }
+        }
+        assert!(used.iter().all(|&used| used));
+
+        let hull = ConvexPolygon2::convex_hull(points.iter().copied()).unwrap();
+        let on_hull = points
+            .iter()
+            .filter(|&&pt| hull.edges().any(|Segment2([a, b])| on_segment(pt, a, b)))
+            .count();
+        let hull_edges = sut
+            .neighbours()
+            .iter()
+            .flatten()
+            .filter(|n| n.is_none())
+            .count();
+        assert_eq!(hull_edges, on_hull);
+        assert_eq!(sut.len(), 2 * points.len() - on_hull - 2);
+        let area: f32 = sut.triangles().map(|triangle| triangle.area()).sum();
+        assert!(
+            (area - hull.area()).abs() < 1e-5 * hull.area(),
+            "{area} {}",
+            hull.area()
+        );
+    }
+
+    #[test]
+    fn test_grid() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(3);
+        let mut points: Vec<Vec2> = (0..12)
+            .flat_map(|x| (0..9).map(move |y| Vec2::new(x as f32, y as f32)))
+            .collect();
+        for _ in 0..3 {
+            // Shuffled, then long and thin, where flat hulls lost triangles.
+            let sut = Delaunay2::new(points.iter().copied());
+            assert_complete(&sut);
+            for triangle in sut.triangles() {
+                let [a, b, c] = triangle.0;
+                for &pt in &points {
+                    assert_ne!(incircle(a, b, c, pt), InCircle::Inside);
+                }
+            }
+            for i in (1..points.len()).rev() {
+                points.swap(i, rng.gen_range(0..=i));
+            }
+            points.iter_mut().for_each(|pt| pt.x *= 1e3);
+        }
+    }
+
+    #[test]
+    fn test_flat_hull() {
+        // The triangle on the bottom edge has a circumcircle reaching far
+        // below it, past where a large enclosing triangle's corner would be.
+        let sut = Delaunay2::new([
+            Vec2::ZERO,
+            Vec2::new(100.0, 0.0),
+            Vec2::new(50.0, 100.0),
+            Vec2::new(50.0, 0.01),
+        ]);
+        assert_complete(&sut);
+        assert_eq!(sut.len(), 3);
+    }
+
+    #[test]
+    fn test_cocircular() {
+        // Every integer point at distance 5 from the centre.
+        let offsets = [
+            (5, 0),
+            (4, 3),
+            (3, 4),
+            (0, 5),
+            (-3, 4),
+            (-4, 3),
+            (-5, 0),
+            (-4, -3),
+            (-3, -4),
+            (0, -5),
+            (3, -4),
+            (4, -3),
+        ];
+        let center = Vec2::new(1000.0, -20.0);
+        let ring: Vec<Vec2> = offsets
+            .iter()
+            .map(|&(x, y)| center + Vec2::new(x as f32, y as f32))
+            .collect();
+        let sut = Delaunay2::new(ring.iter().copied());
+        assert_complete(&sut);
+        assert_eq!(sut.len(), ring.len() - 2);
+
+        let sut = Delaunay2::new(ring.iter().copied().chain([center]));
+        assert_complete(&sut);
+        assert_eq!(sut.len(), ring.len());
+    }
+
+    #[test]
+    fn test_duplicates_and_degenerate() {
+        let sut = Delaunay2::new([Vec2::ZERO, Vec2::X, Vec2::Y, Vec2::X, Vec2::ONE]);
+        assert_eq!(sut.points(), [Vec2::ZERO, Vec2::X, Vec2::Y, Vec2::ONE]);
+        assert_eq!(sut.len(), 2);
+
+        assert!(Delaunay2::new([Vec2::ZERO, Vec2::ONE, Vec2::splat(2.0)]).is_empty());
+        assert!(Delaunay2::new([]).is_empty());
+    }
+
+    #[test]
+    fn test_voronoi_cells() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(2);
+        let points = random_points(&mut rng, 50);
+        let sut = Delaunay2::new(points.iter().copied());
+        let bounds = Aabb2::new(Vec2::ZERO, Vec2::splat(100.0));
+        let cells = sut.voronoi_cells(&bounds);
+
+        let area: f32 = cells.iter().flatten().map(ConvexPolygon2::area).sum();
+        assert!((area - 100.0 * 100.0).abs() < 1.0, "{area}");
+
+        for _ in 0..200 {
+            let pt = Vec2::new(rng.gen_range(0.0..100.0), rng.gen_range(0.0..100.0));
+            let nearest = (0..points.len())
+                .min_by(|&a, &b| {
+                    points[a]
+                        .distance_squared(pt)
+                        .total_cmp(&points[b].distance_squared(pt))
+                })
+                .unwrap();
+            assert!(cells[nearest].as_ref().unwrap().contains(pt));
+        }
+    }
+
+    #[test]
+    fn test_voronoi_cells_collinear() {
+        let bounds = Aabb2::new(Vec2::ZERO, Vec2::splat(10.0));
+        let points = [2.0, 8.0, 4.0].map(|t| Vec2::new(t, t));
+        let sut = Delaunay2::new(points);
+        assert!(sut.is_empty());
+        let cells = sut.voronoi_cells(&bounds);
+
+        let area: f32 = cells.iter().flatten().map(ConvexPolygon2::area).sum();
+        assert!((area - 100.0).abs() < 1e-3, "{area}");
+        // Strips between the bisectors x + y = 6 and x + y = 12.
+        assert!(cells[0].as_ref().unwrap().contains(Vec2::new(0.0, 5.0)));
+        assert!(!cells[0].as_ref().unwrap().contains(Vec2::new(0.0, 7.0)));
+        assert!(cells[2].as_ref().unwrap().contains(Vec2::new(0.0, 7.0)));
+        assert!(!cells[2].as_ref().unwrap().contains(Vec2::new(10.0, 3.0)));
+        assert!(cells[1].as_ref().unwrap().contains(Vec2::new(10.0, 3.0)));
+
+        let single = Delaunay2::new([Vec2::splat(5.0)]).voronoi_cells(&bounds);
+        assert!((single[0].as_ref().unwrap().area() - 100.0).abs() < 1e-3);
+    }
+}
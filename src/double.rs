@@ -0,0 +1,226 @@
+use core::ops::{Index, IndexMut};
+
+use glam::{DVec2, DVec3};
+
+use crate::{
+    predicates::{orient2d_f64, Orientation},
+    quadrilateral::{quad_kind, quad_triangles, Quad2, QuadKind},
+    triangle::{triangle_contains, Boundary, Triangle2},
+};
+
+// `f64` versions of `Triangle2` and `Quad2`, for worlds large enough that
+// `f32` loses precision far from the origin. They cover the core queries;
+// convert to `f32` relative to a nearby origin, such as the camera, for
+// everything else.
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DTriangle2(pub [DVec2; 3]);
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DQuad2(pub [DVec2; 4]);
+
+impl DTriangle2 {
+    /// Points on the edges count as inside.
+    pub fn contains(&self, pt: DVec2) -> bool {
+        self.contains_with(pt, Boundary::Inclusive)
+    }
+
+    /// Exact for any winding, as `Triangle2::contains_with`.
+    pub fn contains_with(&self, pt: DVec2, boundary: Boundary) -> bool {
+        triangle_contains(self.0, pt, boundary)
+    }
+
+    pub fn orientation(&self) -> Orientation {
+        let [v1, v2, v3] = self.0;
+        orient2d_f64(v1, v2, v3)
+    }
+    /// Positive for `Orientation::Positive` windings.
+    pub fn signed_area(&self) -> f64 {
+        let [v1, v2, v3] = self.0;
+        0.5 * (v2 - v1).perp_dot(v3 - v1)
+    }
+    pub fn area(&self) -> f64 {
+        self.signed_area().abs()
+    }
+    pub fn centroid(&self) -> DVec2 {
+        let [v1, v2, v3] = self.0;
+        (v1 + v2 + v3) / 3.0
+    }
+
+    /// `None` if the triangle is degenerate.
+    pub fn barycentric(&self, pt: DVec2) -> Option<DVec3> {
+        let [v1, v2, v3] = self.0;
+        let (e1, e2, r) = (v2 - v1, v3 - v1, pt - v1);
+        let denom = e1.perp_dot(e2);
+        if denom == 0.0 {
+            return None;
+        }
+        let w2 = r.perp_dot(e2) / denom;
+        let w3 = e1.perp_dot(r) / denom;
+        Some(DVec3::new(1.0 - w2 - w3, w2, w3))
+    }
+    pub fn from_barycentric(&self, weights: DVec3) -> DVec2 {
+        let [v1, v2, v3] = self.0;
+        v1 * weights.x + v2 * weights.y + v3 * weights.z
+    }
+
+    pub fn translate(&self, offset: DVec2) -> Self {
+        DTriangle2(self.0.map(|v| v + offset))
+    }
+
+    /// The `f32` triangle relative to `origin`, precise when `origin` is near.
+    pub fn to_local(&self, origin: DVec2) -> Triangle2 {
+        Triangle2(self.0.map(|v| (v - origin).as_vec2()))
+    }
+    pub fn from_local(triangle: &Triangle2, origin: DVec2) -> Self {
+        DTriangle2(triangle.0.map(|v| origin + v.as_dvec2()))
+    }
+}
+
+impl DQuad2 {
+    /// Points on the edges count as inside. Bow-ties follow the even-odd rule,
+    /// as `Quad2::contains`.
+    pub fn contains(&self, pt: DVec2) -> bool {
+        let [t1, t2] = self.triangles();
+        t1.contains(pt) || t2.contains(pt)
+    }
+
+    pub fn kind(&self) -> QuadKind {
+        quad_kind(self.0)
+    }
+
+    /// As `Quad2::triangles`.
+    pub fn triangles(&self) -> [DTriangle2; 2] {
+        quad_triangles(self.0).map(DTriangle2)
+    }
+
+    /// As `Quad2::signed_area`, the net area.
+    pub fn signed_area(&self) -> f64 {
+        let [v1, v2, v3, v4] = self.0;
+        0.5 * (v3 - v1).perp_dot(v4 - v2)
+    }
+    /// As `Quad2::area`, the area covered.
+    pub fn area(&self) -> f64 {
+        match self.kind() {
+            QuadKind::Complex => self.triangles().iter().map(DTriangle2::area).sum(),
+            _ => self.signed_area().abs(),
+        }
+    }
+    /// The centre of mass, or the average vertex if the quad has no area.
+    pub fn centroid(&self) -> DVec2 {
+        let [v1, v2, v3, v4] = self.0;
+        let (t1, t2) = (DTriangle2([v1, v2, v3]), DTriangle2([v1, v3, v4]));
+        let (a1, a2) = (t1.signed_area(), t2.signed_area());
+        if a1 + a2 == 0.0 {
+            return (v1 + v2 + v3 + v4) / 4.0;
+        }
+        (t1.centroid() * a1 + t2.centroid() * a2) / (a1 + a2)
+    }
+
+    pub fn translate(&self, offset: DVec2) -> Self {
+        DQuad2(self.0.map(|v| v + offset))
+    }
+
+    /// The `f32` quad relative to `origin`, precise when `origin` is near.
+    pub fn to_local(&self, origin: DVec2) -> Quad2 {
+        Quad2(self.0.map(|v| (v - origin).as_vec2()))
+    }
+    pub fn from_local(quad: &Quad2, origin: DVec2) -> Self {
+        DQuad2(quad.0.map(|v| origin + v.as_dvec2()))
+    }
+}
+
+impl From<Triangle2> for DTriangle2 {
+    fn from(triangle: Triangle2) -> Self {
+        DTriangle2(triangle.0.map(|v| v.as_dvec2()))
+    }
+}
+impl From<Quad2> for DQuad2 {
+    fn from(quad: Quad2) -> Self {
+        DQuad2(quad.0.map(|v| v.as_dvec2()))
+    }
+}
+
+impl Index<usize> for DTriangle2 {
+    type Output = DVec2;
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.0[index]
+    }
+}
+impl IndexMut<usize> for DTriangle2 {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        &mut self.0[index]
+    }
+}
+impl Index<usize> for DQuad2 {
+    type Output = DVec2;
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.0[index]
+    }
+}
+impl IndexMut<usize> for DQuad2 {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        &mut self.0[index]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::Vec2;
+
+    use super::*;
+
+    /// A million kilometres in metres, where `f32` can't tell apart points
+    /// closer than 64 metres.
+    const FAR: f64 = 1e9;
+
+    #[test]
+    fn test_far_from_origin() {
+        let origin = DVec2::new(FAR, -FAR);
+        let local = Triangle2([Vec2::ZERO, Vec2::new(1.0, 0.0), Vec2::new(0.0, 1.0)]);
+        let sut = DTriangle2::from_local(&local, origin);
+
+        assert_eq!(sut.area(), 0.5);
+        assert!(sut.contains(origin + DVec2::new(0.25, 0.25)));
+        assert!(sut.contains(origin + DVec2::new(0.5, 0.5)));
+        assert!(!sut.contains(origin + DVec2::new(0.5, 0.5001)));
+        assert!(!sut.contains_with(origin + DVec2::new(0.5, 0.5), Boundary::Exclusive));
+        assert_eq!(
+            sut.barycentric(origin + DVec2::new(0.25, 0.5)),
+            Some(DVec3::new(0.25, 0.25, 0.5))
+        );
+        assert_eq!(sut.to_local(origin), local);
+
+        let quad = DQuad2::from_local(
+            &Quad2([
+                Vec2::ZERO,
+                Vec2::new(2.0, 1.0),
+                Vec2::new(4.0, 0.0),
+                Vec2::new(2.0, 4.0),
+            ]),
+            origin,
+        );
+        assert_eq!(quad.kind(), QuadKind::Concave(1));
+        assert!(quad.contains(origin + DVec2::new(2.0, 2.0)));
+        assert!(!quad.contains(origin + DVec2::new(2.0, 0.5)));
+        assert_eq!(quad.area(), 6.0);
+    }
+
+    #[test]
+    fn test_matches_f32() {
+        let quad = Quad2([
+            Vec2::ZERO,
+            Vec2::new(2.0, 2.0),
+            Vec2::new(2.0, 0.0),
+            Vec2::new(0.0, 2.0),
+        ]);
+        let sut = DQuad2::from(quad);
+        assert_eq!(sut.kind(), quad.kind());
+        assert_eq!(sut.area(), 2.0);
+        assert_eq!(sut.area() as f32, quad.area());
+        assert_eq!(sut.centroid().as_vec2(), quad.centroid());
+        for (d, f) in sut.triangles().iter().zip(quad.triangles()) {
+            assert_eq!(DTriangle2::from(f), *d);
+        }
+    }
+}
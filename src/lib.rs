@@ -2,11 +2,33 @@
 
 extern crate alloc;
 
+pub mod aabb;
 pub mod bitset2d;
+pub mod bvh;
+pub mod circle;
+pub mod clipping;
+pub mod collision;
+pub mod convex_polygon;
+pub mod delaunay;
+pub mod double;
 pub mod frame_stats;
 pub mod genetics;
 pub mod logging;
+#[cfg(feature = "macroquad-render")]
+pub mod mesh_batch;
+pub mod navmesh;
+pub mod occupancy_pyramid;
+pub mod offset;
+pub mod polygon;
+pub mod predicates;
+pub mod procgen;
 pub mod quadrilateral;
+pub mod raster;
+pub mod sampling;
+pub mod segment;
+pub mod shape;
+pub mod tile_collision;
 pub mod triangle;
+pub mod triangle3;
 pub mod update_steps;
 pub mod vm;
@@ -0,0 +1,269 @@
+use alloc::{vec, vec::Vec};
+use glam::{Vec2, Vec3};
+use macroquad::{
+    models::{draw_mesh, Mesh, Vertex},
+    prelude::Color,
+    texture::Texture2D,
+};
+
+use crate::quadrilateral::{project, quad_crossing, Quad2, QuadKind};
+
+// Kept below macroquad's default draw call capacity, which clamps larger meshes.
+const MAX_VERTICES: usize = 8000;
+const MAX_INDICES: usize = 4800;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Warp {
+    Bilinear,
+    Projective,
+}
+
+/// Collects textured shapes so they can be drawn together, splitting into
+/// several meshes when they would exceed a single draw call.
+pub struct MeshBatch {
+    texture: Option<Texture2D>,
+    meshes: Vec<Mesh>,
+}
+impl MeshBatch {
+    pub fn new(texture: Option<Texture2D>) -> Self {
+        Self {
+            texture,
+            meshes: Vec::new(),
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.meshes.clear();
+    }
+    pub fn is_empty(&self) -> bool {
+        self.meshes.is_empty()
+    }
+    pub fn meshes(&self) -> &[Mesh] {
+        &self.meshes
+    }
+
+    pub fn draw(&self) {
+        for mesh in &self.meshes {
+            draw_mesh(mesh);
+        }
+    }
+
+    pub fn push_triangle(
+        &mut self,
+        triangle: &crate::triangle::Triangle2,
+        uvs: [Vec2; 3],
+        colors: [Color; 3],
+    ) {
+        let vertices = [0, 1, 2].map(|i| vertex(triangle[i], uvs[i], colors[i]));
+        self.push(&vertices, &[0, 1, 2]);
+    }
+
+    /// Splits concave quads at their reflex vertex, and bow-ties into their two
+    /// lobes, as `Quad2::triangles` does.
+    pub fn push_quad(&mut self, quad: &Quad2, uvs: [Vec2; 4], colors: [Color; 4]) {
+        let vertex_at = |i: usize| vertex(quad[i & 3], uvs[i & 3], colors[i & 3]);
+        match quad.kind() {
+            QuadKind::Convex => self.push(&[0, 1, 2, 3].map(vertex_at), &[0, 1, 2, 2, 3, 0]),
+            QuadKind::Concave(reflex) => {
+                let vertices = [0, 1, 2, 3].map(|offset| vertex_at(reflex + offset));
+                self.push(&vertices, &[0, 1, 2, 2, 3, 0]);
+            }
+            QuadKind::Complex => {
+                let (i, t) = quad_crossing(quad.0);
+                let (a, b) = (i & 3, (i + 1) & 3);
+                let crossing = vertex(
+                    quad[a].lerp(quad[b], t),
+                    uvs[a].lerp(uvs[b], t),
+                    lerp_color(colors[a], colors[b], t),
+                );
+                let vertices = [
+                    crossing,
+                    vertex_at(i + 1),
+                    vertex_at(i + 2),
+                    vertex_at(i + 3),
+                    vertex_at(i),
+                ];
+                self.push(&vertices, &[0, 1, 2, 0, 3, 4]);
+            }
+        }
+    }
+
+    /// Tessellates the quad into a grid so the whole texture follows `warp`,
+    /// which a pair of affinely textured triangles cannot do.
+    pub fn push_warped_quad(
+        &mut self,
+        quad: &Quad2,
+        warp: Warp,
+        subdivisions: usize,
+        color: Color,
+    ) {
+        let steps = subdivisions.max(1);
+        let homography = match warp {
+            Warp::Bilinear => None,
+            Warp::Projective => match quad.homography() {
+                Some(homography) => Some(homography),
+                None => return,
+            },
+        };
+        let map = |uv: Vec2| match homography {
+            Some(homography) => project(homography, uv),
+            None => Some(quad.bilinear(uv)),
+        };
+
+        // Each row is pushed in chunks of columns to stay within the draw call
+        // limits, however finely the quad is subdivided.
+        let chunk = MAX_INDICES / 6;
+        for row in 0..steps {
+            for start in (0..steps).step_by(chunk) {
+                let columns = chunk.min(steps - start);
+                let mut vertices = Vec::with_capacity(2 * (columns + 1));
+                let mut indices = Vec::with_capacity(6 * columns);
+                let mut finite = Vec::with_capacity(2 * (columns + 1));
+                for column in start..=start + columns {
+                    for y in [row, row + 1] {
+                        let uv = Vec2::new(column as f32, y as f32) / steps as f32;
+                        let position = map(uv);
+                        finite.push(position.is_some());
+                        vertices.push(vertex(position.unwrap_or(Vec2::ZERO), uv, color));
+                    }
+                }
+                for column in 0..columns as u16 {
+                    let i = 2 * column;
+                    // Cells with a corner mapped to infinity are left out.
+                    if finite[i as usize..i as usize + 4].iter().all(|&f| f) {
+                        indices.extend([i, i + 2, i + 3, i + 3, i + 1, i]);
+                    }
+                }
+                self.push(&vertices, &indices);
+            }
+        }
+    }
+
+    /// Indices are relative to the pushed vertices.
+    fn push(&mut self, vertices: &[Vertex], indices: &[u16]) {
+        let fits = self.meshes.last().is_some_and(|mesh| {
+            mesh.vertices.len() + vertices.len() <= MAX_VERTICES
+                && mesh.indices.len() + indices.len() <= MAX_INDICES
+        });
+        if !fits {
+            self.meshes.push(Mesh {
+                vertices: Vec::new(),
+                indices: vec![],
+                texture: self.texture,
+            });
+        }
+        let mesh = self.meshes.last_mut().unwrap();
+        let offset = mesh.vertices.len() as u16;
+        mesh.vertices.extend_from_slice(vertices);
+        mesh.indices.extend(indices.iter().map(|i| offset + i));
+    }
+}
+
+fn vertex(position: Vec2, uv: Vec2, color: Color) -> Vertex {
+    Vertex {
+        position: Vec3::new(position.x, position.y, 0.0),
+        uv,
+        color,
+    }
+}
+
+fn lerp_color(a: Color, b: Color, t: f32) -> Color {
+    let lerp = |a: f32, b: f32| a + (b - a) * t;
+    Color::new(
+        lerp(a.r, b.r),
+        lerp(a.g, b.g),
+        lerp(a.b, b.b),
+        lerp(a.a, b.a),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use macroquad::prelude::WHITE;
+
+    use super::*;
+    use crate::{aabb::Aabb2, triangle::Triangle2};
+
+    fn assert_within_limits(sut: &MeshBatch) {
+        for mesh in sut.meshes() {
+            assert!(mesh.vertices.len() <= MAX_VERTICES);
+            assert!(mesh.indices.len() <= MAX_INDICES);
+            assert!(mesh
+                .indices
+                .iter()
+                .all(|&i| (i as usize) < mesh.vertices.len()));
+        }
+    }
+
+    #[test]
+    fn test_push_shapes() {
+        let mut sut = MeshBatch::new(None);
+        assert!(sut.is_empty());
+        let square = Quad2::from_aabb(&Aabb2::new(Vec2::ZERO, Vec2::ONE));
+        sut.push_quad(&square, square.0, [WHITE; 4]);
+        assert_eq!(sut.meshes().len(), 1);
+        assert_eq!(sut.meshes()[0].indices, [0, 1, 2, 2, 3, 0]);
+
+        let bow_tie = Quad2([Vec2::ZERO, Vec2::ONE, Vec2::X, Vec2::Y]);
+        sut.push_quad(&bow_tie, bow_tie.0, [WHITE; 4]);
+        assert_eq!(sut.meshes()[0].vertices.len(), 4 + 5);
+
+        // Enough triangles to spill into a second mesh.
+        let triangle = Triangle2([Vec2::ZERO, Vec2::X, Vec2::Y]);
+        for _ in 0..MAX_INDICES / 3 {
+            sut.push_triangle(&triangle, triangle.0, [WHITE; 3]);
+        }
+        assert_eq!(sut.meshes().len(), 2);
+        assert_within_limits(&sut);
+
+        sut.clear();
+        assert!(sut.is_empty());
+    }
+
+    #[test]
+    fn test_push_warped_quad() {
+        let quad = Quad2([
+            Vec2::ZERO,
+            Vec2::new(4.0, 0.0),
+            Vec2::new(3.0, 2.0),
+            Vec2::new(1.0, 2.0),
+        ]);
+        for warp in [Warp::Bilinear, Warp::Projective] {
+            let mut sut = MeshBatch::new(None);
+            sut.push_warped_quad(&quad, warp, 4, WHITE);
+            assert_eq!(sut.meshes().len(), 1);
+            assert_eq!(sut.meshes()[0].indices.len(), 6 * 4 * 4);
+            let corners = sut.meshes()[0]
+                .vertices
+                .iter()
+                .map(|v| v.position.truncate());
+            assert!(corners.clone().any(|v| v.abs_diff_eq(quad[2], 1e-5)));
+        }
+
+        // Rows wider than one mesh are split rather than overflowing it.
+        let steps = MAX_INDICES / 6 + 1;
+        let mut sut = MeshBatch::new(None);
+        sut.push_warped_quad(&quad, Warp::Bilinear, steps, WHITE);
+        assert_within_limits(&sut);
+        let indices: usize = sut.meshes().iter().map(|mesh| mesh.indices.len()).sum();
+        assert_eq!(indices, 6 * steps * steps);
+
+        // A concave quad's projective warp sends the line u + v = 2/3 to
+        // infinity, which passes through grid points.
+        let concave = Quad2([
+            Vec2::ZERO,
+            Vec2::new(4.0, 0.0),
+            Vec2::new(1.0, 1.0),
+            Vec2::new(0.0, 4.0),
+        ]);
+        let mut sut = MeshBatch::new(None);
+        sut.push_warped_quad(&concave, Warp::Projective, 3, WHITE);
+        assert_within_limits(&sut);
+        let mesh = &sut.meshes()[0];
+        assert_eq!(mesh.indices.len(), 6 * (3 * 3 - 6));
+        assert!(mesh
+            .indices
+            .iter()
+            .all(|&i| mesh.vertices[i as usize].position.is_finite()));
+    }
+}
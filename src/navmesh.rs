@@ -0,0 +1,276 @@
+use alloc::{
+    collections::{BTreeMap, BinaryHeap},
+    vec,
+    vec::Vec,
+};
+use core::cmp::Ordering;
+
+use glam::Vec2;
+
+use crate::{
+    predicates::{orient2d, Orientation},
+    triangle::Triangle2,
+};
+
+/// Walkable space as triangles, where units can move freely within each
+/// triangle and between triangles sharing an edge.
+///
+/// Triangles are stored with `Orientation::Positive` winding, and neighbour
+/// `i` of a triangle is across the edge opposite its vertex `i`. Edges are
+/// only shared when both triangles use exactly the same vertices.
+#[derive(Clone, Debug)]
+pub struct NavMesh {
+    triangles: Vec<Triangle2>,
+    neighbours: Vec<[Option<usize>; 3]>,
+}
+
+impl NavMesh {
+    pub fn new(triangles: impl IntoIterator<Item = Triangle2>) -> Self {
+        let triangles: Vec<Triangle2> = triangles
+            .into_iter()
+            .map(|triangle| match triangle.orientation() {
+                Orientation::Negative => {
+                    let [v1, v2, v3] = triangle.0;
+                    Triangle2([v1, v3, v2])
+                }
+                _ => triangle,
+            })
+            .collect();
+
+        let key = |v: Vec2| (v.x.to_bits(), v.y.to_bits());
+        let mut edges = BTreeMap::new();
+        for (index, triangle) in triangles.iter().enumerate() {
+            for edge in 0..3 {
+                let (a, b) = portal(triangle, edge);
+                edges.insert((key(a), key(b)), index);
+            }
+        }
+        let neighbours = triangles
+            .iter()
+            .map(|triangle| {
+                [0, 1, 2].map(|edge| {
+                    let (a, b) = portal(triangle, edge);
+                    edges.get(&(key(b), key(a))).copied()
+                })
+            })
+            .collect();
+        Self {
+            triangles,
+            neighbours,
+        }
+    }
+
+    pub fn triangles(&self) -> &[Triangle2] {
+        &self.triangles
+    }
+    pub fn neighbours(&self) -> &[[Option<usize>; 3]] {
+        &self.neighbours
+    }
+
+    /// The first triangle containing `pt`, including its edges.
+    pub fn locate(&self, pt: Vec2) -> Option<usize> {
+        self.triangles
+            .iter()
+            .position(|triangle| triangle.contains(pt))
+    }
+
+    /// The shortest path from `from` to `to` through the mesh, including both
+    /// ends, or `None` if either is off the mesh or they aren't connected.
+    pub fn find_path(&self, from: Vec2, to: Vec2) -> Option<Vec<Vec2>> {
+        let corridor = self.find_corridor(from, to)?;
+        Some(self.string_pull(from, to, &corridor))
+    }
+
+    /// The triangles crossed going from `from` to `to`, using A* between edge
+    /// midpoints. This is near-shortest, but not always exactly.
+    pub fn find_corridor(&self, from: Vec2, to: Vec2) -> Option<Vec<usize>> {
+        let (start, goal) = (self.locate(from)?, self.locate(to)?);
+
+        let mut best = vec![(f32::INFINITY, None); self.triangles.len()];
+        let mut entry = vec![from; self.triangles.len()];
+        best[start].0 = 0.0;
+        let mut open = BinaryHeap::from([Open {
+            estimate: from.distance(to),
+            triangle: start,
+        }]);
+
+        while let Some(Open { estimate, triangle }) = open.pop() {
+            if triangle == goal {
+                let mut corridor = vec![goal];
+                while let Some(previous) = best[*corridor.last()?].1 {
+                    corridor.push(previous);
+                }
+                corridor.reverse();
+                return Some(corridor);
+            }
+            let cost = best[triangle].0;
+            if estimate > cost + entry[triangle].distance(to) {
+                // Stale entry, superseded by a cheaper route.
+                continue;
+            }
+
+            for (edge, neighbour) in self.neighbours[triangle].iter().enumerate() {
+                let Some(neighbour) = *neighbour else {
+                    continue;
+                };
+                let (a, b) = portal(&self.triangles[triangle], edge);
+                let midpoint = (a + b) * 0.5;
+                let next_cost = cost + entry[triangle].distance(midpoint);
+                if next_cost < best[neighbour].0 {
+                    best[neighbour] = (next_cost, Some(triangle));
+                    entry[neighbour] = midpoint;
+                    open.push(Open {
+                        estimate: next_cost + midpoint.distance(to),
+                        triangle: neighbour,
+                    });
+                }
+            }
+        }
+        None
+    }
+
+    /// Shortens a path through `corridor` with the funnel algorithm, so it
+    /// only turns at corners of the mesh.
+    pub fn string_pull(&self, from: Vec2, to: Vec2, corridor: &[usize]) -> Vec<Vec2> {
+        // Each portal as `(left, right)` when walking through it.
+        let mut portals = vec![(from, from)];
+        for pair in corridor.windows(2) {
+            let edge = self.neighbours[pair[0]]
+                .iter()
+                .position(|&n| n == Some(pair[1]));
+            if let Some(edge) = edge {
+                let (right, left) = portal(&self.triangles[pair[0]], edge);
+                portals.push((left, right));
+            }
+        }
+        portals.push((to, to));
+
+        let mut path = vec![from];
+        let (mut apex, mut left, mut right) = (from, from, from);
+        let (mut left_index, mut right_index) = (0, 0);
+        let mut i = 1;
+        while i < portals.len() {
+            let (portal_left, portal_right) = portals[i];
+
+            if orient2d(apex, right, portal_right) != Orientation::Negative {
+                if apex == right || orient2d(apex, left, portal_right) == Orientation::Negative {
+                    right = portal_right;
+                    right_index = i;
+                } else {
+                    // The right side crossed over the left, so turn at the left.
+                    path.push(left);
+                    apex = left;
+                    (right, right_index) = (apex, left_index);
+                    i = left_index + 1;
+                    continue;
+                }
+            }
+
+            if orient2d(apex, left, portal_left) != Orientation::Positive {
+                if apex == left || orient2d(apex, right, portal_left) == Orientation::Positive {
+                    left = portal_left;
+                    left_index = i;
+                } else {
+                    path.push(right);
+                    apex = right;
+                    (left, left_index) = (apex, right_index);
+                    i = right_index + 1;
+                    continue;
+                }
+            }
+            i += 1;
+        }
+        if path.last() != Some(&to) {
+            path.push(to);
+        }
+        path
+    }
+}
+
+/// The edge opposite vertex `i`, in the triangle's winding, which puts the
+/// interior on its left.
+fn portal(triangle: &Triangle2, i: usize) -> (Vec2, Vec2) {
+    (triangle[(i + 1) % 3], triangle[(i + 2) % 3])
+}
+
+/// A triangle waiting to be expanded, ordered so `BinaryHeap` pops the lowest
+/// estimate first.
+struct Open {
+    estimate: f32,
+    triangle: usize,
+}
+impl PartialEq for Open {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+impl Eq for Open {}
+impl PartialOrd for Open {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for Open {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.estimate.total_cmp(&self.estimate)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::polygon::Polygon2;
+
+    /// A U-shaped corridor: up the left, across the top, down the right.
+    fn u_shape() -> NavMesh {
+        let outline = [
+            (0.0, 0.0),
+            (2.0, 0.0),
+            (2.0, 8.0),
+            (8.0, 8.0),
+            (8.0, 0.0),
+            (10.0, 0.0),
+            (10.0, 10.0),
+            (0.0, 10.0),
+        ];
+        let polygon =
+            Polygon2::new(outline.iter().map(|&(x, y)| Vec2::new(x, y)).collect()).unwrap();
+        NavMesh::new(polygon.triangulate().unwrap())
+    }
+
+    #[test]
+    fn test_adjacency_and_locate() {
+        let sut = u_shape();
+        let shared: usize = sut
+            .neighbours()
+            .iter()
+            .map(|n| n.iter().flatten().count())
+            .sum();
+        // Each interior diagonal is shared by two triangles.
+        assert_eq!(shared, 2 * (sut.triangles().len() - 1));
+
+        assert!(sut.locate(Vec2::new(1.0, 1.0)).is_some());
+        assert!(sut.locate(Vec2::new(5.0, 5.0)).is_none());
+        assert!(sut.locate(Vec2::new(11.0, 5.0)).is_none());
+    }
+
+    #[test]
+    fn test_find_path() {
+        let sut = u_shape();
+        let (from, to) = (Vec2::new(1.0, 1.0), Vec2::new(9.0, 1.0));
+        let path = sut.find_path(from, to).unwrap();
+        assert_eq!(path, [from, Vec2::new(2.0, 8.0), Vec2::new(8.0, 8.0), to]);
+
+        // Straight line when nothing's in the way.
+        let (from, to) = (Vec2::new(1.0, 9.0), Vec2::new(9.0, 9.5));
+        assert_eq!(sut.find_path(from, to).unwrap(), [from, to]);
+        assert_eq!(sut.find_path(from, from).unwrap(), [from]);
+
+        assert_eq!(sut.find_path(from, Vec2::new(5.0, 5.0)), None);
+        let island = NavMesh::new([
+            Triangle2([Vec2::ZERO, Vec2::X, Vec2::Y]),
+            Triangle2([Vec2::splat(5.0), Vec2::new(6.0, 5.0), Vec2::new(5.0, 6.0)]),
+        ]);
+        assert_eq!(island.find_path(Vec2::splat(0.1), Vec2::splat(5.1)), None);
+    }
+}
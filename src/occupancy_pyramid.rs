@@ -0,0 +1,360 @@
+use core::ops::Range;
+
+use alloc::vec::Vec;
+use glam::Vec2;
+use libm::floorf;
+
+use crate::bitset2d::{set_bits, BitSet2d};
+
+/// A `BitSet2d` with mip-style summaries, where each level marks the 8x8
+/// blocks of the level below that have any cell set.
+///
+/// Level `n` summarises `8^n` by `8^n` cells, so queries over large, mostly
+/// empty maps can skip whole regions at once.
+pub struct OccupancyPyramid {
+    base: BitSet2d,
+    levels: Vec<BitSet2d>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RaycastHit {
+    pub cell: (isize, isize),
+    pub distance: f32,
+    /// The face of `cell` the ray entered through, or zero if it started inside.
+    pub normal: Vec2,
+}
+
+impl OccupancyPyramid {
+    pub fn new(depth: usize) -> Self {
+        Self::from_bitset(BitSet2d::new(), depth)
+    }
+
+    pub fn from_bitset(base: BitSet2d, depth: usize) -> Self {
+        let mut levels = Vec::with_capacity(depth);
+        for level in 1..=depth {
+            let mut summary = BitSet2d::new();
+            for (x, y) in base.iter() {
+                summary.insert((x >> (3 * level), y >> (3 * level)));
+            }
+            levels.push(summary);
+        }
+        Self { base, levels }
+    }
+
+    pub fn base(&self) -> &BitSet2d {
+        &self.base
+    }
+    pub fn into_inner(self) -> BitSet2d {
+        self.base
+    }
+    pub fn depth(&self) -> usize {
+        self.levels.len()
+    }
+
+    pub fn insert(&mut self, (x, y): (isize, isize)) {
+        self.base.insert((x, y));
+        for (index, summary) in self.levels.iter_mut().enumerate() {
+            let shift = 3 * (index + 1);
+            summary.insert((x >> shift, y >> shift));
+        }
+    }
+
+    pub fn remove(&mut self, (x, y): (isize, isize)) -> bool {
+        if !self.base.remove((x, y)) {
+            return false;
+        }
+        for level in 1..=self.levels.len() {
+            let shift = 3 * level;
+            let block = (x >> shift, y >> shift);
+            if self.level(level - 1).block8x8(block) != 0 {
+                break;
+            }
+            self.levels[level - 1].remove(block);
+        }
+        true
+    }
+
+    pub fn contains(&self, (x, y): (isize, isize)) -> bool {
+        self.base.contains((x, y))
+    }
+
+    /// Iterates over the set cells in an unspecified order.
+    pub fn iter(&self) -> Iter<'_> {
+        self.iter_rect(isize::MIN..isize::MAX, isize::MIN..isize::MAX)
+    }
+
+    /// Iterates over the set cells inside the rect, in an unspecified order.
+    pub fn iter_rect(&self, x: Range<isize>, y: Range<isize>) -> Iter<'_> {
+        let mut iter = Iter {
+            pyramid: self,
+            x,
+            y,
+            stack: Vec::new(),
+            cells: (0, (0, 0)),
+        };
+        if self.levels.is_empty() {
+            iter.stack.extend(self.base.iter().map(|(x, y)| (0, x, y)));
+        } else {
+            let top = self.levels.len();
+            iter.stack
+                .extend(self.level(top).iter().map(|(x, y)| (top, x, y)));
+        }
+        iter.stack
+            .retain(|&block| block_intersects(block, &iter.x, &iter.y));
+        iter.stack.reverse();
+        iter
+    }
+
+    pub fn any_in_rect(&self, x: Range<isize>, y: Range<isize>) -> bool {
+        self.iter_rect(x, y).next().is_some()
+    }
+
+    /// Finds the first set cell along the ray, stepping over empty blocks.
+    ///
+    /// `direction` does not need to be normalised; `distance` is measured in
+    /// multiples of it. `max_distance` may be infinite; the ray gives up once
+    /// it leaves the bounds of the set cells.
+    pub fn raycast(&self, origin: Vec2, direction: Vec2, max_distance: f32) -> Option<RaycastHit> {
+        let (x_bounds, y_bounds) = self.base.bounds();
+        let max_distance = max_distance
+            .min(exit_distance(origin.x, direction.x, &x_bounds))
+            .min(exit_distance(origin.y, direction.y, &y_bounds));
+        let mut cell = (floorf(origin.x) as isize, floorf(origin.y) as isize);
+        let mut distance = 0.0;
+        let mut normal = Vec2::ZERO;
+        loop {
+            if self.base.contains(cell) {
+                return Some(RaycastHit {
+                    cell,
+                    distance,
+                    normal,
+                });
+            }
+
+            // The largest empty block around `cell`.
+            let mut shift = 0;
+            for level in 1..=self.levels.len() {
+                if self
+                    .level(level)
+                    .contains((cell.0 >> (3 * level), cell.1 >> (3 * level)))
+                {
+                    break;
+                }
+                shift = 3 * level;
+            }
+            let x_span = ((cell.0 >> shift) << shift)..(((cell.0 >> shift) + 1) << shift);
+            let y_span = ((cell.1 >> shift) << shift)..(((cell.1 >> shift) + 1) << shift);
+
+            let exit_x = exit_distance(origin.x, direction.x, &x_span);
+            let exit_y = exit_distance(origin.y, direction.y, &y_span);
+            distance = exit_x.min(exit_y);
+            if distance > max_distance || distance == f32::INFINITY {
+                return None;
+            }
+
+            let pt = origin + direction * distance;
+            let clamp = |value: f32, span: &Range<isize>| {
+                (floorf(value) as isize).clamp(span.start, span.end - 1)
+            };
+            if exit_x <= exit_y {
+                let step = direction.x.signum();
+                cell = (
+                    if step > 0.0 {
+                        x_span.end
+                    } else {
+                        x_span.start - 1
+                    },
+                    clamp(pt.y, &y_span),
+                );
+                normal = Vec2::new(-step, 0.0);
+            } else {
+                let step = direction.y.signum();
+                cell = (
+                    clamp(pt.x, &x_span),
+                    if step > 0.0 {
+                        y_span.end
+                    } else {
+                        y_span.start - 1
+                    },
+                );
+                normal = Vec2::new(0.0, -step);
+            }
+        }
+    }
+
+    fn level(&self, level: usize) -> &BitSet2d {
+        if level == 0 {
+            &self.base
+        } else {
+            &self.levels[level - 1]
+        }
+    }
+}
+
+fn block_intersects(
+    (level, x, y): (usize, isize, isize),
+    rx: &Range<isize>,
+    ry: &Range<isize>,
+) -> bool {
+    let shift = 3 * level;
+    let x_span = (x << shift)..((x + 1) << shift);
+    let y_span = (y << shift)..((y + 1) << shift);
+    x_span.start < rx.end && rx.start < x_span.end && y_span.start < ry.end && ry.start < y_span.end
+}
+
+fn exit_distance(origin: f32, direction: f32, span: &Range<isize>) -> f32 {
+    if direction > 0.0 {
+        (span.end as f32 - origin) / direction
+    } else if direction < 0.0 {
+        (span.start as f32 - origin) / direction
+    } else {
+        f32::INFINITY
+    }
+}
+
+pub struct Iter<'a> {
+    pyramid: &'a OccupancyPyramid,
+    x: Range<isize>,
+    y: Range<isize>,
+    /// Non-empty blocks still to visit, as `(level, x, y)`.
+    stack: Vec<(usize, isize, isize)>,
+    /// Set cells of the current 8x8 block, and the block's first cell.
+    cells: (u64, (isize, isize)),
+}
+impl Iterator for Iter<'_> {
+    type Item = (isize, isize);
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (cells, (x0, y0)) = &mut self.cells;
+            while *cells != 0 {
+                let bit = cells.trailing_zeros() as isize;
+                *cells &= *cells - 1;
+                let pt = (*x0 + (bit & 7), *y0 + (bit >> 3));
+                if self.x.contains(&pt.0) && self.y.contains(&pt.1) {
+                    return Some(pt);
+                }
+            }
+
+            let (level, x, y) = self.stack.pop()?;
+            match level {
+                0 => {
+                    // Only reached without summaries, where the stack holds the cells.
+                    return Some((x, y));
+                }
+                1 => {
+                    self.cells = (self.pyramid.base.block8x8((x, y)), (x << 3, y << 3));
+                }
+                _ => {
+                    let block = self.pyramid.level(level - 1).block8x8((x, y));
+                    for bit in set_bits(block) {
+                        let child = (
+                            level - 1,
+                            (x << 3) + (bit & 7) as isize,
+                            (y << 3) + (bit >> 3) as isize,
+                        );
+                        if block_intersects(child, &self.x, &self.y) {
+                            self.stack.push(child);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{Rng, SeedableRng};
+
+    use super::*;
+
+    fn random_pyramid(rng: &mut impl Rng, count: usize) -> OccupancyPyramid {
+        let mut sut = OccupancyPyramid::new(2);
+        for _ in 0..count {
+            sut.insert((rng.gen_range(-300..300), rng.gen_range(-300..300)));
+        }
+        sut
+    }
+
+    #[test]
+    fn test_iter_rect() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+        for _ in 0..50 {
+            let sut = random_pyramid(&mut rng, 40);
+            let x = rng.gen_range(-320..0)..rng.gen_range(0..320);
+            let y = rng.gen_range(-320..0)..rng.gen_range(0..320);
+
+            let mut expected: Vec<_> = sut
+                .base()
+                .iter()
+                .filter(|(px, py)| x.contains(px) && y.contains(py))
+                .collect();
+            let mut actual: Vec<_> = sut.iter_rect(x.clone(), y.clone()).collect();
+            expected.sort();
+            actual.sort();
+            assert_eq!(actual, expected);
+            assert_eq!(sut.any_in_rect(x, y), !expected.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut sut = OccupancyPyramid::new(3);
+        sut.insert((-1, -1));
+        sut.insert((-2, -1));
+        sut.insert((1000, 0));
+        assert!(sut.remove((-1, -1)));
+        assert!(sut.any_in_rect(-8..0, -8..0));
+        assert!(sut.remove((-2, -1)));
+        assert!(!sut.remove((-2, -1)));
+        assert!(!sut.any_in_rect(-8..0, -8..0));
+        assert!(!sut.levels[2].contains((-1, -1)));
+        assert_eq!(sut.iter().collect::<Vec<_>>(), [(1000, 0)]);
+    }
+
+    #[test]
+    fn test_raycast() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(1);
+        for _ in 0..200 {
+            let sut = random_pyramid(&mut rng, 200);
+            let origin = Vec2::new(rng.gen_range(-300.0..300.0), rng.gen_range(-300.0..300.0));
+            let direction = Vec2::from_angle(rng.gen_range(0.0..6.3));
+
+            // Brute force by marching in small steps.
+            let expected = (0..40_000)
+                .map(|i| i as f32 * 0.01)
+                .map(|t| origin + direction * t)
+                .map(|pt| (floorf(pt.x) as isize, floorf(pt.y) as isize))
+                .find(|&cell| sut.contains(cell));
+
+            let actual = sut.raycast(origin, direction, 400.0);
+            assert_eq!(
+                actual.map(|hit| hit.cell),
+                expected,
+                "{origin:?} {direction:?}"
+            );
+        }
+    }
+    #[test]
+    fn test_raycast_unbounded() {
+        let mut sut = OccupancyPyramid::new(2);
+        sut.insert((3, 5));
+        sut.insert((-70, 40));
+
+        let far = f32::INFINITY;
+        let hit = sut.raycast(Vec2::new(0.5, 5.5), Vec2::X, far).unwrap();
+        assert_eq!((hit.cell, hit.distance), ((3, 5), 2.5));
+        assert_eq!(sut.raycast(Vec2::new(0.5, 6.5), Vec2::X, far), None);
+        assert_eq!(sut.raycast(Vec2::new(0.5, 5.5), -Vec2::X, far), None);
+        assert_eq!(
+            sut.raycast(Vec2::new(0.5, 0.5), Vec2::new(1.0, 0.3), far),
+            None
+        );
+        assert_eq!(sut.raycast(Vec2::new(0.5, 1e6), Vec2::Y, far), None);
+        assert_eq!(sut.raycast(Vec2::new(0.5, 0.5), Vec2::ZERO, far), None);
+        assert_eq!(
+            OccupancyPyramid::new(2).raycast(Vec2::ZERO, Vec2::ONE, far),
+            None
+        );
+    }
+}
@@ -0,0 +1,86 @@
+use alloc::vec::Vec;
+use glam::Vec2;
+use libm::{acosf, atan2f, ceilf};
+
+/// How to fill the gap that opens at a corner when its edges are moved apart
+/// by offsetting.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Join {
+    /// Extends the edges until they meet, unless that point would be more
+    /// than `limit` times the offset distance from the corner, in which case
+    /// the corner is cut off flat.
+    Miter { limit: f32 },
+    /// An arc around the corner, made of chords that fall at most
+    /// `tolerance` inside it.
+    Round { tolerance: f32 },
+}
+
+const MAX_ARC_SEGMENTS: f32 = 128.0;
+
+/// Moves each edge of `ring`, which must have `Orientation::Positive`
+/// winding, outwards by `distance`, or inwards if it's negative. `None` if an
+/// edge ends up reversed, which means the result folds over itself.
+pub(crate) fn offset_ring(ring: &[Vec2], distance: f32, join: Join) -> Option<Vec<Vec2>> {
+    let mut ring = ring.to_vec();
+    ring.dedup();
+    if ring.len() > 1 && ring.first() == ring.last() {
+        ring.pop();
+    }
+    let len = ring.len();
+    let normal = |i: usize| {
+        let edge = ring[(i + 1) % len] - ring[i];
+        Vec2::new(edge.y, -edge.x).normalize_or_zero()
+    };
+
+    // Each corner's points, where corner `i` joins edge `i - 1` to edge `i`.
+    let corners: Vec<Vec<Vec2>> = (0..len)
+        .map(|i| {
+            let (n0, n1) = (normal((i + len - 1) % len), normal(i));
+            corner(ring[i], n0, n1, distance, join)
+        })
+        .collect();
+
+    for i in 0..len {
+        let next = (i + 1) % len;
+        let (Some(&start), Some(&end)) = (corners[i].last(), corners[next].first()) else {
+            continue;
+        };
+        if (end - start).dot(ring[next] - ring[i]) < 0.0 {
+            return None;
+        }
+    }
+    Some(corners.into_iter().flatten().collect())
+}
+
+fn corner(vertex: Vec2, n0: Vec2, n1: Vec2, distance: f32, join: Join) -> Vec<Vec2> {
+    let turn = n0.perp_dot(n1);
+    let cos = n0.dot(n1);
+    // Where both moved edges meet, when they aren't close to folding back.
+    let miter = (cos > -0.99).then(|| (n0 + n1) / (1.0 + cos));
+
+    if turn * distance <= 0.0 {
+        // The edges overlap rather than open a gap, so trim them where they
+        // cross.
+        return Vec::from([vertex + miter.unwrap_or(n0) * distance]);
+    }
+    match join {
+        Join::Miter { limit } => match miter {
+            Some(miter) if miter.length() <= limit => Vec::from([vertex + miter * distance]),
+            _ => Vec::from([vertex + n0 * distance, vertex + n1 * distance]),
+        },
+        Join::Round { tolerance } => {
+            let angle = atan2f(turn, cos);
+            let radius = distance.abs();
+            let step = 2.0 * acosf((1.0 - tolerance / radius).clamp(-1.0, 1.0));
+            let segments = ceilf(angle.abs() / step).clamp(1.0, MAX_ARC_SEGMENTS) as usize;
+            let mut arc = Vec::with_capacity(segments + 1);
+            arc.push(vertex + n0 * distance);
+            for i in 1..segments {
+                let rotation = Vec2::from_angle(angle * i as f32 / segments as f32);
+                arc.push(vertex + rotation.rotate(n0) * distance);
+            }
+            arc.push(vertex + n1 * distance);
+            arc
+        }
+    }
+}
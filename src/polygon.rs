@@ -0,0 +1,396 @@
+use alloc::vec::Vec;
+use glam::Vec2;
+
+use crate::{
+    aabb::Aabb2,
+    convex_polygon::ConvexPolygon2,
+    predicates::{on_segment, orient2d, segments_cross, Orientation},
+    segment::Segment2,
+    triangle::Triangle2,
+};
+
+/// A simple polygon with optional holes, such as a contour from a level
+/// editor.
+///
+/// The outline is stored with `Orientation::Positive` winding and holes with
+/// `Orientation::Negative`. Holes are expected to lie inside the outline
+/// without touching it or each other, and no ring may cross itself; this
+/// isn't checked.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Polygon2 {
+    outline: Vec<Vec2>,
+    holes: Vec<Vec<Vec2>>,
+}
+impl Polygon2 {
+    /// `None` if the outline has no area, in either winding.
+    pub fn new(outline: Vec<Vec2>) -> Option<Self> {
+        Self::with_holes(outline, Vec::new())
+    }
+
+    /// `None` if the outline or any hole has no area. Rings may be given in
+    /// either winding.
+    pub fn with_holes(outline: Vec<Vec2>, holes: Vec<Vec<Vec2>>) -> Option<Self> {
+        let outline = wound(outline, Orientation::Positive)?;
+        let holes = holes
+            .into_iter()
+            .map(|hole| wound(hole, Orientation::Negative))
+            .collect::<Option<_>>()?;
+        Some(Self { outline, holes })
+    }
+
+    pub fn outline(&self) -> &[Vec2] {
+        &self.outline
+    }
+    pub fn holes(&self) -> &[Vec<Vec2>] {
+        &self.holes
+    }
+    /// The outline followed by each hole.
+    pub fn rings(&self) -> impl Iterator<Item = &[Vec2]> {
+        core::iter::once(self.outline.as_slice()).chain(self.holes.iter().map(Vec::as_slice))
+    }
+    pub fn edges(&self) -> impl Iterator<Item = Segment2> + '_ {
+        self.rings().flat_map(ring_edges)
+    }
+
+    /// How many times the rings wind around `pt`, counting holes negatively,
+    /// so it's `1` inside and `0` in holes and outside.
+    pub fn winding_number(&self, pt: Vec2) -> i32 {
+        self.edges()
+            .map(|Segment2([a, b])| {
+                let side = orient2d(a, b, pt);
+                if a.y <= pt.y && pt.y < b.y && side == Orientation::Positive {
+                    1
+                } else if b.y <= pt.y && pt.y < a.y && side == Orientation::Negative {
+                    -1
+                } else {
+                    0
+                }
+            })
+            .sum()
+    }
+
+    /// Points on any ring, including hole boundaries, count as inside.
+    pub fn contains(&self, pt: Vec2) -> bool {
+        self.edges().any(|Segment2([a, b])| on_segment(pt, a, b)) || self.winding_number(pt) != 0
+    }
+
+    pub fn area(&self) -> f32 {
+        self.rings().map(signed_area).sum()
+    }
+    pub fn aabb(&self) -> Aabb2 {
+        Aabb2::from_points(self.outline.iter().copied())
+    }
+
+    /// Ear clipping, after bridging each hole to the outline. Gives
+    /// `Orientation::Positive` triangles, `n - 2 + 2h` of them for `n` vertices
+    /// and `h` holes, with runtime quadratic in the vertex count. `None` if a
+    /// hole can't be joined to the outline, as when it crosses the outline, or
+    /// if ear clipping runs out of ears, as it can when a ring crosses itself.
+    pub fn triangulate(&self) -> Option<Vec<Triangle2>> {
+        let mut ring = self.outline.clone();
+        for (index, hole) in self.holes.iter().enumerate() {
+            bridge(&mut ring, hole, &self.holes[index + 1..])?;
+        }
+        ear_clip(ring)
+    }
+
+    /// Hertel–Mehlhorn: triangulates, then removes diagonals while the pieces
+    /// either side stay convex. Gives at most four times the minimum number of
+    /// pieces. `None` where `triangulate` is.
+    pub fn convex_decomposition(&self) -> Option<Vec<ConvexPolygon2>> {
+        let mut pieces: Vec<Vec<Vec2>> = self
+            .triangulate()?
+            .into_iter()
+            .filter(|triangle| triangle.orientation() == Orientation::Positive)
+            .map(|triangle| triangle.0.to_vec())
+            .collect();
+
+        let mut merged = true;
+        while merged {
+            merged = false;
+            'search: for i in 0..pieces.len() {
+                for j in (i + 1)..pieces.len() {
+                    if let Some(piece) = merge_convex(&pieces[i], &pieces[j]) {
+                        pieces[i] = piece;
+                        pieces.swap_remove(j);
+                        merged = true;
+                        break 'search;
+                    }
+                }
+            }
+        }
+        Some(pieces.into_iter().filter_map(ConvexPolygon2::new).collect())
+    }
+}
+
+/// Splices `hole` into `ring` through a pair of mutually visible vertices,
+/// so `ring` stays a single closed walk around the remaining area. `None` if
+/// no ring vertex is visible from the hole.
+fn bridge(ring: &mut Vec<Vec2>, hole: &[Vec2], remaining: &[Vec<Vec2>]) -> Option<()> {
+    // The rightmost hole vertex sees the ring unless other holes are in
+    // the way, which the visibility test handles.
+    let (hole_index, from) = hole
+        .iter()
+        .copied()
+        .enumerate()
+        .max_by(|a, b| a.1.x.total_cmp(&b.1.x))
+        .unwrap_or((0, hole[0]));
+
+    let blockers: Vec<Segment2> = ring_edges(ring)
+        .chain(ring_edges(hole))
+        .chain(remaining.iter().flat_map(|hole| ring_edges(hole)))
+        .collect();
+    let mut candidates: Vec<usize> = (0..ring.len()).collect();
+    candidates.sort_by(|&a, &b| {
+        ring[a]
+            .distance_squared(from)
+            .total_cmp(&ring[b].distance_squared(from))
+    });
+    let visible = candidates.into_iter().find(|&i| {
+        let to = ring[i];
+        let clear = blockers.iter().all(|&Segment2([a, b])| {
+            !segments_cross(from, to, a, b) && (a == from || a == to || !on_segment(a, from, to))
+        });
+        clear && enters_between(ring, i, from)
+    });
+    let ring_index = visible?;
+
+    let mut spliced = Vec::with_capacity(ring.len() + hole.len() + 2);
+    spliced.extend_from_slice(&ring[..=ring_index]);
+    spliced.extend_from_slice(&hole[hole_index..]);
+    spliced.extend_from_slice(&hole[..=hole_index]);
+    spliced.extend_from_slice(&ring[ring_index..]);
+    *ring = spliced;
+    Some(())
+}
+
+/// Whether the direction from `ring[i]` to `pt` lies inside the ring's
+/// interior angle at `i`.
+fn enters_between(ring: &[Vec2], i: usize, pt: Vec2) -> bool {
+    let len = ring.len();
+    let (prev, v, next) = (ring[(i + len - 1) % len], ring[i], ring[(i + 1) % len]);
+    let left_of_next = orient2d(v, next, pt) == Orientation::Positive;
+    let left_of_prev = orient2d(prev, v, pt) == Orientation::Positive;
+    if orient2d(prev, v, next) == Orientation::Negative {
+        left_of_next || left_of_prev
+    } else {
+        left_of_next && left_of_prev
+    }
+}
+
+fn ear_clip(mut ring: Vec<Vec2>) -> Option<Vec<Triangle2>> {
+    let mut triangles = Vec::with_capacity(ring.len().saturating_sub(2));
+    let mut misses = 0;
+    let mut i = 0;
+    while ring.len() > 3 {
+        let len = ring.len();
+        i %= len;
+        let (prev, v, next) = (ring[(i + len - 1) % len], ring[i], ring[(i + 1) % len]);
+        let turn = orient2d(prev, v, next);
+        let is_ear = match turn {
+            Orientation::Positive => !ring.iter().any(|&pt| {
+                pt != prev && pt != v && pt != next && Triangle2([prev, v, next]).contains(pt)
+            }),
+            // After a full lap without ears only degenerate corners remain,
+            // such as straight runs, which are safe to drop.
+            Orientation::Collinear => misses >= len,
+            Orientation::Negative => false,
+        };
+        if is_ear {
+            if turn == Orientation::Positive {
+                triangles.push(Triangle2([prev, v, next]));
+            }
+            ring.remove(i);
+            misses = 0;
+        } else {
+            i += 1;
+            misses += 1;
+            if misses > 2 * len {
+                // Not a simple polygon; stop rather than loop forever.
+                return None;
+            }
+        }
+    }
+    if ring.len() == 3 {
+        triangles.push(Triangle2([ring[0], ring[1], ring[2]]));
+    }
+    Some(triangles)
+}
+
+/// Joins two `Orientation::Positive` convex pieces that share an edge, if the
+/// result is still convex.
+fn merge_convex(a: &[Vec2], b: &[Vec2]) -> Option<Vec<Vec2>> {
+    let (a_len, b_len) = (a.len(), b.len());
+    for i in 0..a_len {
+        let (start, end) = (a[i], a[(i + 1) % a_len]);
+        let Some(j) = (0..b_len).find(|&j| b[j] == end && b[(j + 1) % b_len] == start) else {
+            continue;
+        };
+        // Walk `a` from `end` round to `start`, then `b` from after `start`
+        // round to before `end`.
+        let mut merged: Vec<Vec2> = (1..=a_len).map(|k| a[(i + k) % a_len]).collect();
+        merged.extend((2..b_len).map(|k| b[(j + k) % b_len]));
+        let len = merged.len();
+        let convex = (0..len).all(|k| {
+            orient2d(merged[k], merged[(k + 1) % len], merged[(k + 2) % len])
+                != Orientation::Negative
+        });
+        return convex.then_some(merged);
+    }
+    None
+}
+
+fn ring_edges(ring: &[Vec2]) -> impl Iterator<Item = Segment2> + '_ {
+    let len = ring.len();
+    (0..len).map(move |i| Segment2([ring[i], ring[(i + 1) % len]]))
+}
+
+fn signed_area(ring: &[Vec2]) -> f32 {
+    0.5 * ring_edges(ring)
+        .map(|Segment2([a, b])| a.perp_dot(b))
+        .sum::<f32>()
+}
+
+fn wound(mut ring: Vec<Vec2>, winding: Orientation) -> Option<Vec<Vec2>> {
+    ring.dedup();
+    if ring.len() > 1 && ring.first() == ring.last() {
+        ring.pop();
+    }
+    let area = signed_area(&ring);
+    if ring.len() < 3 || area == 0.0 {
+        return None;
+    }
+    let positive = area > 0.0;
+    if positive != (winding == Orientation::Positive) {
+        ring.reverse();
+    }
+    Some(ring)
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+    use rand::{Rng, SeedableRng};
+
+    use super::*;
+
+    fn ring(points: &[(f32, f32)]) -> Vec<Vec2> {
+        points.iter().map(|&(x, y)| Vec2::new(x, y)).collect()
+    }
+
+    fn square_with_holes() -> Polygon2 {
+        Polygon2::with_holes(
+            ring(&[(0.0, 0.0), (10.0, 0.0), (10.0, 10.0), (0.0, 10.0)]),
+            vec![
+                ring(&[(2.0, 2.0), (4.0, 2.0), (4.0, 4.0), (2.0, 4.0)]),
+                ring(&[(6.0, 5.0), (8.0, 5.0), (7.0, 8.0)]),
+            ],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_contains_and_area() {
+        let sut = square_with_holes();
+        assert_eq!(sut.area(), 100.0 - 4.0 - 3.0);
+        assert_eq!(sut.winding_number(Vec2::new(1.0, 1.0)), 1);
+        assert_eq!(sut.winding_number(Vec2::new(3.0, 3.0)), 0);
+        assert_eq!(sut.winding_number(Vec2::new(11.0, 3.0)), 0);
+        assert!(sut.contains(Vec2::new(2.0, 3.0)));
+        assert!(sut.contains(Vec2::new(10.0, 10.0)));
+        assert!(!sut.contains(Vec2::new(7.0, 6.0)));
+
+        // Rings are rewound, so holes given either way cancel the outline.
+        assert!(sut.holes().iter().all(|hole| signed_area(hole) < 0.0));
+        assert_eq!(
+            Polygon2::new(ring(&[(0.0, 0.0), (1.0, 1.0), (2.0, 2.0)])),
+            None
+        );
+    }
+
+    #[test]
+    fn test_triangulate() {
+        // A comb, with deep notches that rule out most diagonals.
+        let comb = Polygon2::new(ring(&[
+            (0.0, 0.0),
+            (5.0, 0.0),
+            (5.0, 4.0),
+            (4.0, 4.0),
+            (4.0, 1.0),
+            (3.0, 1.0),
+            (3.0, 4.0),
+            (2.0, 4.0),
+            (2.0, 1.0),
+            (1.0, 1.0),
+            (1.0, 4.0),
+            (0.0, 4.0),
+        ]))
+        .unwrap();
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+        for (sut, count) in [(comb, 10), (square_with_holes(), 13)] {
+            let triangles = sut.triangulate().unwrap();
+            assert_eq!(triangles.len(), count);
+            let area: f32 = triangles.iter().map(Triangle2::area).sum();
+            assert!((area - sut.area()).abs() < 1e-4, "{area}");
+            for triangle in &triangles {
+                assert_eq!(triangle.orientation(), Orientation::Positive);
+            }
+
+            for _ in 0..500 {
+                let pt = Vec2::new(rng.gen_range(-1.0..11.0), rng.gen_range(-1.0..11.0));
+                let covered = triangles.iter().any(|triangle| triangle.contains(pt));
+                assert_eq!(covered, sut.contains(pt), "{pt:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_convex_decomposition() {
+        let l_shape = Polygon2::new(ring(&[
+            (0.0, 0.0),
+            (4.0, 0.0),
+            (4.0, 1.0),
+            (1.0, 1.0),
+            (1.0, 4.0),
+            (0.0, 4.0),
+        ]))
+        .unwrap();
+        assert_eq!(l_shape.convex_decomposition().unwrap().len(), 2);
+
+        let sut = square_with_holes();
+        let pieces = sut.convex_decomposition().unwrap();
+        assert!(pieces.len() < sut.triangulate().unwrap().len());
+        let area: f32 = pieces.iter().map(ConvexPolygon2::area).sum();
+        assert!((area - sut.area()).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_unbridgeable_hole() {
+        // The hole pokes out through the right side, so every ring vertex it
+        // could join lies outside the area.
+        let sut = Polygon2::with_holes(
+            ring(&[(0.0, 0.0), (10.0, 0.0), (10.0, 10.0), (0.0, 10.0)]),
+            vec![ring(&[(8.0, 4.0), (12.0, 4.0), (12.0, 6.0), (8.0, 6.0)])],
+        )
+        .unwrap();
+        assert_eq!(sut.triangulate(), None);
+        assert_eq!(sut.convex_decomposition(), None);
+    }
+
+    #[test]
+    fn test_self_intersecting() {
+        // Crosses itself twice, leaving no ear to cut part way through.
+        let sut = Polygon2::new(ring(&[
+            (1.0, 3.0),
+            (2.0, 0.0),
+            (1.0, 0.0),
+            (4.0, 3.0),
+            (4.0, 1.0),
+            (2.0, 4.0),
+        ]))
+        .unwrap();
+        assert_eq!(sut.triangulate(), None);
+        assert_eq!(sut.convex_decomposition(), None);
+    }
+}
@@ -0,0 +1,351 @@
+use core::ops::Sub;
+
+use glam::{DVec2, Vec2};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Orientation {
+    /// Counter-clockwise with `y` up, clockwise on screen with `y` down.
+    Positive,
+    Negative,
+    Collinear,
+}
+/// Where a point lies relative to a circle, as given by `incircle`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InCircle {
+    Inside,
+    Outside,
+    Cocircular,
+}
+
+impl Orientation {
+    pub fn reverse(self) -> Self {
+        match self {
+            Orientation::Positive => Orientation::Negative,
+            Orientation::Negative => Orientation::Positive,
+            Orientation::Collinear => Orientation::Collinear,
+        }
+    }
+}
+
+/// The exact sign of `(b - a).perp_dot(c - a)`, free of rounding errors.
+pub fn orient2d(a: Vec2, b: Vec2, c: Vec2) -> Orientation {
+    orientation_of(exact_sum_sign(orient_terms(a, b, c)))
+}
+
+/// The terms of `orient2d`'s determinant. Products of two `f32`s are exact in
+/// `f64`, so only their sum needs care.
+fn orient_terms(a: Vec2, b: Vec2, c: Vec2) -> [f64; 6] {
+    let [ax, ay, bx, by, cx, cy] = [a.x, a.y, b.x, b.y, c.x, c.y].map(f64::from);
+    [
+        ax * by,
+        -(ay * bx),
+        bx * cy,
+        -(by * cx),
+        cx * ay,
+        -(cy * ax),
+    ]
+}
+
+/// `orient2d` for `f64` points, also exact barring overflow and underflow.
+pub fn orient2d_f64(a: DVec2, b: DVec2, c: DVec2) -> Orientation {
+    // Products are split into their rounded value and exact error.
+    let products = [
+        (a.x, b.y),
+        (-a.y, b.x),
+        (b.x, c.y),
+        (-b.y, c.x),
+        (c.x, a.y),
+        (-c.y, a.x),
+    ];
+    let mut terms = [0.0; 12];
+    for (i, (p, q)) in products.into_iter().enumerate() {
+        (terms[2 * i], terms[2 * i + 1]) = two_product(p, q);
+    }
+    orientation_of(exact_sum_sign(terms))
+}
+
+/// The `Vec2` and `DVec2` operations that shape queries need, so they can be
+/// written once for both precisions.
+pub(crate) trait Point: Copy + PartialEq + Sub<Output = Self> {
+    type Scalar;
+    fn orient2d(a: Self, b: Self, c: Self) -> Orientation;
+    /// Whether `self` lies in the box with corners `a` and `b`.
+    fn in_box(self, a: Self, b: Self) -> bool;
+    /// Whether an edge along `self` is a top or left edge, with `y` down.
+    fn is_top_left(self) -> bool;
+    /// How far along `a..b` the line through `c..d` crosses it, which must not
+    /// be parallel.
+    fn crossing(a: Self, b: Self, c: Self, d: Self) -> Self::Scalar;
+    fn lerp(self, rhs: Self, t: Self::Scalar) -> Self;
+}
+
+macro_rules! impl_point {
+    ($point:ty, $scalar:ty, $orient2d:ident) => {
+        impl Point for $point {
+            type Scalar = $scalar;
+            fn orient2d(a: Self, b: Self, c: Self) -> Orientation {
+                $orient2d(a, b, c)
+            }
+            fn in_box(self, a: Self, b: Self) -> bool {
+                self.cmpge(a.min(b)).all() && self.cmple(a.max(b)).all()
+            }
+            fn is_top_left(self) -> bool {
+                self.y < 0.0 || (self.y == 0.0 && self.x > 0.0)
+            }
+            fn crossing(a: Self, b: Self, c: Self, d: Self) -> $scalar {
+                let (r, s) = (b - a, d - c);
+                (c - a).perp_dot(s) / r.perp_dot(s)
+            }
+            fn lerp(self, rhs: Self, t: $scalar) -> Self {
+                <$point>::lerp(self, rhs, t)
+            }
+        }
+    };
+}
+impl_point!(Vec2, f32, orient2d);
+impl_point!(DVec2, f64, orient2d_f64);
+
+/// Whether `pt` lies on the closed segment `a..=b`, exactly.
+pub fn on_segment(pt: Vec2, a: Vec2, b: Vec2) -> bool {
+    on_segment_of(pt, a, b)
+}
+
+pub fn on_segment_f64(pt: DVec2, a: DVec2, b: DVec2) -> bool {
+    on_segment_of(pt, a, b)
+}
+
+pub(crate) fn on_segment_of<P: Point>(pt: P, a: P, b: P) -> bool {
+    P::orient2d(a, b, pt) == Orientation::Collinear && pt.in_box(a, b)
+}
+
+/// Whether the open segments `a..b` and `c..d` cross at a single point,
+/// exactly. Touching endpoints and collinear overlaps do not count.
+pub fn segments_cross(a: Vec2, b: Vec2, c: Vec2, d: Vec2) -> bool {
+    segments_cross_of(a, b, c, d)
+}
+
+pub fn segments_cross_f64(a: DVec2, b: DVec2, c: DVec2, d: DVec2) -> bool {
+    segments_cross_of(a, b, c, d)
+}
+
+pub(crate) fn segments_cross_of<P: Point>(a: P, b: P, c: P, d: P) -> bool {
+    let opposite =
+        |o1: Orientation, o2: Orientation| o1 != Orientation::Collinear && o1.reverse() == o2;
+    opposite(P::orient2d(a, b, c), P::orient2d(a, b, d))
+        && opposite(P::orient2d(c, d, a), P::orient2d(c, d, b))
+}
+
+/// Whether `d` is inside, outside or on the circle through `a`, `b` and `c`,
+/// which must have `Orientation::Positive` winding. Exact, like `orient2d`.
+pub fn incircle(a: Vec2, b: Vec2, c: Vec2, d: Vec2) -> InCircle {
+    // Evaluated in `f64` first, which is only in doubt when the points are
+    // almost cocircular (Shewchuk's error bound).
+    const ERROR_BOUND: f64 = (10.0 + 96.0 * f64::EPSILON / 2.0) * f64::EPSILON / 2.0;
+    let [ad, bd, cd] = [a, b, c].map(|v| v.as_dvec2() - d.as_dvec2());
+    let [a_lift, b_lift, c_lift] = [ad, bd, cd].map(DVec2::length_squared);
+    let det = a_lift * bd.perp_dot(cd) + b_lift * cd.perp_dot(ad) + c_lift * ad.perp_dot(bd);
+    let permanent = ((bd.x * cd.y).abs() + (cd.x * bd.y).abs()) * a_lift
+        + ((cd.x * ad.y).abs() + (ad.x * cd.y).abs()) * b_lift
+        + ((ad.x * bd.y).abs() + (bd.x * ad.y).abs()) * c_lift;
+    if det.abs() > ERROR_BOUND * permanent {
+        in_circle_of(det)
+    } else {
+        in_circle_of(incircle_exact(a, b, c, d))
+    }
+}
+
+/// The sign of the lifted determinant, expanded along the lifted column into
+/// squared lengths times `orient2d` determinants. Each term is a product of
+/// four `f32`s, which is exact as a `two_product` of two exact products.
+fn incircle_exact(a: Vec2, b: Vec2, c: Vec2, d: Vec2) -> f64 {
+    let minors = [
+        (1.0, a, orient_terms(b, c, d)),
+        (-1.0, b, orient_terms(a, c, d)),
+        (1.0, c, orient_terms(a, b, d)),
+        (-1.0, d, orient_terms(a, b, c)),
+    ];
+    let mut terms = [0.0; 96];
+    let mut len = 0;
+    for (sign, pt, minor) in minors {
+        let [x, y] = [pt.x, pt.y].map(f64::from);
+        for lift in [x * x, y * y] {
+            for term in minor {
+                (terms[len], terms[len + 1]) = two_product(sign * lift, term);
+                len += 2;
+            }
+        }
+    }
+    exact_sum_sign(terms)
+}
+
+fn in_circle_of(sign: f64) -> InCircle {
+    if sign > 0.0 {
+        InCircle::Inside
+    } else if sign < 0.0 {
+        InCircle::Outside
+    } else {
+        InCircle::Cocircular
+    }
+}
+
+fn orientation_of(sign: f64) -> Orientation {
+    if sign > 0.0 {
+        Orientation::Positive
+    } else if sign < 0.0 {
+        Orientation::Negative
+    } else {
+        Orientation::Collinear
+    }
+}
+
+/// Sums the terms as an expansion (Shewchuk's grow-expansion) and returns a
+/// value with the sign of the exact sum.
+fn exact_sum_sign<const N: usize>(terms: [f64; N]) -> f64 {
+    let mut expansion = [0.0; N];
+    for (len, term) in terms.into_iter().enumerate() {
+        let mut sum = term;
+        for component in &mut expansion[..len] {
+            let (s, error) = two_sum(sum, *component);
+            *component = error;
+            sum = s;
+        }
+        expansion[len] = sum;
+    }
+    // Components are non-overlapping and increasing in magnitude.
+    expansion
+        .into_iter()
+        .rev()
+        .find(|component| *component != 0.0)
+        .unwrap_or(0.0)
+}
+
+fn two_product(a: f64, b: f64) -> (f64, f64) {
+    let product = a * b;
+    (product, libm::fma(a, b, -product))
+}
+
+fn two_sum(a: f64, b: f64) -> (f64, f64) {
+    let sum = a + b;
+    let b_virtual = sum - a;
+    let a_virtual = sum - b_virtual;
+    (sum, (a - a_virtual) + (b - b_virtual))
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{Rng, SeedableRng};
+
+    use super::*;
+
+    #[test]
+    fn test_orient2d_near_collinear() {
+        // Naive `f32` evaluation gets these wrong or inconsistent.
+        let a = Vec2::new(0.5, 0.5);
+        let b = Vec2::new(12.0, 12.0);
+        let c = Vec2::new(24.0, 24.0);
+        assert_eq!(orient2d(a, b, c), Orientation::Collinear);
+
+        let nudged = Vec2::new(24.0, f32::from_bits(24.0f32.to_bits() + 1));
+        assert_eq!(orient2d(a, b, nudged), Orientation::Positive);
+        assert_eq!(orient2d(b, a, nudged), Orientation::Negative);
+        assert_eq!(orient2d(nudged, a, b), Orientation::Positive);
+    }
+
+    #[test]
+    fn test_segments_cross() {
+        let (a, b) = (Vec2::new(0.0, 0.0), Vec2::new(2.0, 2.0));
+        assert!(segments_cross(
+            a,
+            b,
+            Vec2::new(0.0, 2.0),
+            Vec2::new(2.0, 0.0)
+        ));
+        assert!(!segments_cross(
+            a,
+            b,
+            Vec2::new(1.0, 1.0),
+            Vec2::new(2.0, 0.0)
+        ));
+        assert!(!segments_cross(
+            a,
+            b,
+            Vec2::new(1.0, 1.0),
+            Vec2::new(3.0, 3.0)
+        ));
+        assert!(!segments_cross(
+            a,
+            b,
+            Vec2::new(3.0, 0.0),
+            Vec2::new(0.0, 3.0).lerp(Vec2::new(3.0, 0.0), 0.9)
+        ));
+    }
+
+    #[test]
+    fn test_on_segment() {
+        let (a, b) = (Vec2::new(0.0, 0.0), Vec2::new(3.0, 1.5));
+        assert!(on_segment(Vec2::new(2.0, 1.0), a, b));
+        assert!(on_segment(a, a, b));
+        assert!(!on_segment(Vec2::new(4.0, 2.0), a, b));
+        assert!(!on_segment(Vec2::new(2.0, 1.1), a, b));
+    }
+
+    #[test]
+    fn test_incircle() {
+        let (a, b, c) = (Vec2::ZERO, Vec2::new(2.0, 0.0), Vec2::new(0.0, 2.0));
+        assert_eq!(incircle(a, b, c, Vec2::ONE), InCircle::Inside);
+        assert_eq!(incircle(a, b, c, Vec2::splat(2.0)), InCircle::Cocircular);
+        assert_eq!(incircle(a, b, c, Vec2::splat(2.1)), InCircle::Outside);
+
+        // Against integer arithmetic on a small grid far from the origin,
+        // where cocircular points are common and cancel to nothing.
+        let exact = |[a, b, c, d]: [[i64; 2]; 4]| {
+            let [a, b, c] = [a, b, c].map(|[x, y]| [x - d[0], y - d[1]]);
+            let lift = |[x, y]: [i64; 2]| x * x + y * y;
+            let cross = |[ax, ay]: [i64; 2], [bx, by]: [i64; 2]| ax * by - ay * bx;
+            lift(a) * cross(b, c) + lift(b) * cross(c, a) + lift(c) * cross(a, b)
+        };
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+        let mut cocircular = 0;
+        for _ in 0..5000 {
+            let mut ints: [[i64; 2]; 4] =
+                core::array::from_fn(|_| [rng.gen_range(-6..=6), rng.gen_range(-6..=6)]);
+            // Exact in `f32`, with a quarter unit between neighbours.
+            let [mut a, mut b, c, d] =
+                ints.map(|[x, y]| Vec2::new(x as f32, y as f32) * 0.25 + 2097152.0);
+            match orient2d(a, b, c) {
+                Orientation::Positive => {}
+                Orientation::Negative => {
+                    (a, b) = (b, a);
+                    ints.swap(0, 1);
+                }
+                Orientation::Collinear => continue,
+            }
+            let expected = in_circle_of(exact(ints) as f64);
+            cocircular += usize::from(expected == InCircle::Cocircular);
+            assert_eq!(incircle(a, b, c, d), expected, "{ints:?}");
+            // Without the `f64` filter, which decides most cases.
+            assert_eq!(in_circle_of(incircle_exact(a, b, c, d)), expected);
+        }
+        assert!(cocircular > 10, "{cocircular}");
+    }
+
+    #[test]
+    fn test_orient2d_f64_near_collinear() {
+        // Far from the origin, where the products need more than 53 bits.
+        let offset = DVec2::splat(1e9);
+        let a = offset + DVec2::new(0.5, 0.5);
+        let b = offset + DVec2::new(12.0, 12.0);
+        let c = offset + DVec2::new(24.0, 24.0);
+        assert_eq!(orient2d_f64(a, b, c), Orientation::Collinear);
+
+        let nudged = DVec2::new(c.x, f64::from_bits(c.y.to_bits() + 1));
+        assert_eq!(orient2d_f64(a, b, nudged), Orientation::Positive);
+        assert_eq!(orient2d_f64(b, a, nudged), Orientation::Negative);
+        assert!(segments_cross_f64(
+            a,
+            c,
+            DVec2::new(b.x, a.y),
+            DVec2::new(b.x, c.y)
+        ));
+    }
+}
@@ -0,0 +1,349 @@
+use core::ops::Range;
+
+use alloc::{vec, vec::Vec};
+use rand::Rng;
+
+use crate::bitset2d::BitSet2d;
+
+// All generators cover `0..width` by `0..height`, with set cells being walls
+// and the outer border always solid, so maps can be used directly with
+// `TileCollider`.
+
+/// Random fill followed by cellular automaton smoothing, giving organic caves.
+///
+/// A cell becomes a wall when at least 5 of the 9 cells around it are walls,
+/// counting anything outside the map as wall.
+pub fn cellular_cave(
+    rng: &mut impl Rng,
+    width: usize,
+    height: usize,
+    fill_probability: f64,
+    iterations: usize,
+) -> BitSet2d {
+    let mut grid = Grid::new(width, height, true);
+    for y in 1..height.saturating_sub(1) {
+        for x in 1..width.saturating_sub(1) {
+            grid.set(x, y, rng.gen_bool(fill_probability));
+        }
+    }
+
+    for _ in 0..iterations {
+        let mut next = grid.clone();
+        for y in 1..height.saturating_sub(1) {
+            for x in 1..width.saturating_sub(1) {
+                next.set(x, y, grid.count_walls_around(x, y) >= 5);
+            }
+        }
+        grid = next;
+    }
+    grid.to_bitset()
+}
+
+/// Carves a random walk through solid rock, starting in the centre, until
+/// `floor_fraction` of the interior is open.
+pub fn drunkards_walk(
+    rng: &mut impl Rng,
+    width: usize,
+    height: usize,
+    floor_fraction: f64,
+) -> BitSet2d {
+    let mut grid = Grid::new(width, height, true);
+    if width < 3 || height < 3 {
+        return grid.to_bitset();
+    }
+
+    let interior = (width - 2) * (height - 2);
+    let target = ((interior as f64 * floor_fraction) as usize).clamp(1, interior);
+    let (mut x, mut y) = (width / 2, height / 2);
+    let mut carved = 0;
+    loop {
+        if grid.get(x, y) {
+            grid.set(x, y, false);
+            carved += 1;
+            if carved == target {
+                break;
+            }
+        }
+        match rng.gen_range(0..4) {
+            0 => x = (x + 1).min(width - 2),
+            1 => x = (x - 1).max(1),
+            2 => y = (y + 1).min(height - 2),
+            _ => y = (y - 1).max(1),
+        }
+    }
+    grid.to_bitset()
+}
+
+/// Recursively splits the map into leaves no smaller than `min_leaf_size`,
+/// places a room in each, and joins sibling rooms with L-shaped corridors.
+pub fn bsp_dungeon(
+    rng: &mut impl Rng,
+    width: usize,
+    height: usize,
+    min_leaf_size: usize,
+) -> BitSet2d {
+    assert!(
+        min_leaf_size >= 3,
+        "leaves need space for a room and its walls"
+    );
+    let mut grid = Grid::new(width, height, true);
+    if width >= 2 + min_leaf_size && height >= 2 + min_leaf_size {
+        bsp_split(
+            rng,
+            &mut grid,
+            1..(width - 1),
+            1..(height - 1),
+            min_leaf_size,
+        );
+    }
+    grid.to_bitset()
+}
+
+/// Thresholded value noise, where `cell_size` is the spacing of the random
+/// lattice and cells whose noise is below `threshold` become walls.
+pub fn value_noise(
+    rng: &mut impl Rng,
+    width: usize,
+    height: usize,
+    cell_size: usize,
+    threshold: f32,
+) -> BitSet2d {
+    assert!(cell_size > 0);
+    let lattice_width = width / cell_size + 2;
+    let lattice_height = height / cell_size + 2;
+    let lattice: Vec<f32> = (0..(lattice_width * lattice_height))
+        .map(|_| rng.gen())
+        .collect();
+    let lattice = |x: usize, y: usize| lattice[x + y * lattice_width];
+
+    let mut grid = Grid::new(width, height, true);
+    for y in 1..height.saturating_sub(1) {
+        let (iy, ty) = (
+            y / cell_size,
+            smoothstep((y % cell_size) as f32 / cell_size as f32),
+        );
+        for x in 1..width.saturating_sub(1) {
+            let (ix, tx) = (
+                x / cell_size,
+                smoothstep((x % cell_size) as f32 / cell_size as f32),
+            );
+            let top = lerp(lattice(ix, iy), lattice(ix + 1, iy), tx);
+            let bottom = lerp(lattice(ix, iy + 1), lattice(ix + 1, iy + 1), tx);
+            grid.set(x, y, lerp(top, bottom, ty) < threshold);
+        }
+    }
+    grid.to_bitset()
+}
+
+/// Returns a random open cell in the carved region, to connect to.
+fn bsp_split(
+    rng: &mut impl Rng,
+    grid: &mut Grid,
+    x: Range<usize>,
+    y: Range<usize>,
+    min_leaf_size: usize,
+) -> (usize, usize) {
+    let can_split_x = x.len() >= 2 * min_leaf_size;
+    let can_split_y = y.len() >= 2 * min_leaf_size;
+    let split_x = match (can_split_x, can_split_y) {
+        (false, false) => return carve_room(rng, grid, x, y),
+        (true, false) => true,
+        (false, true) => false,
+        (true, true) => match x.len().cmp(&y.len()) {
+            core::cmp::Ordering::Greater => true,
+            core::cmp::Ordering::Less => false,
+            core::cmp::Ordering::Equal => rng.gen(),
+        },
+    };
+
+    let (a, b) = if split_x {
+        let mid = rng.gen_range((x.start + min_leaf_size)..=(x.end - min_leaf_size));
+        (
+            bsp_split(rng, grid, x.start..mid, y.clone(), min_leaf_size),
+            bsp_split(rng, grid, mid..x.end, y, min_leaf_size),
+        )
+    } else {
+        let mid = rng.gen_range((y.start + min_leaf_size)..=(y.end - min_leaf_size));
+        (
+            bsp_split(rng, grid, x.clone(), y.start..mid, min_leaf_size),
+            bsp_split(rng, grid, x, mid..y.end, min_leaf_size),
+        )
+    };
+
+    let corner = if rng.gen() { (a.0, b.1) } else { (b.0, a.1) };
+    carve_line(grid, a, corner);
+    carve_line(grid, corner, b);
+    if rng.gen() {
+        a
+    } else {
+        b
+    }
+}
+
+fn carve_room(
+    rng: &mut impl Rng,
+    grid: &mut Grid,
+    x: Range<usize>,
+    y: Range<usize>,
+) -> (usize, usize) {
+    // Keep a one cell margin so neighbouring rooms don't merge.
+    let available = (x.len() - 2, y.len() - 2);
+    let size = (
+        rng.gen_range(available.0.div_ceil(2)..=available.0),
+        rng.gen_range(available.1.div_ceil(2)..=available.1),
+    );
+    let x0 = x.start + 1 + rng.gen_range(0..=(available.0 - size.0));
+    let y0 = y.start + 1 + rng.gen_range(0..=(available.1 - size.1));
+    for y in y0..(y0 + size.1) {
+        for x in x0..(x0 + size.0) {
+            grid.set(x, y, false);
+        }
+    }
+    (
+        rng.gen_range(x0..(x0 + size.0)),
+        rng.gen_range(y0..(y0 + size.1)),
+    )
+}
+
+/// Carves an axis-aligned line, inclusive of both ends.
+fn carve_line(grid: &mut Grid, from: (usize, usize), to: (usize, usize)) {
+    for y in from.1.min(to.1)..=from.1.max(to.1) {
+        for x in from.0.min(to.0)..=from.0.max(to.0) {
+            grid.set(x, y, false);
+        }
+    }
+}
+
+fn lerp(a: f32, b: f32, t: f32) -> f32 {
+    a + (b - a) * t
+}
+fn smoothstep(t: f32) -> f32 {
+    t * t * (3.0 - 2.0 * t)
+}
+
+/// Dense scratch space, as the automaton reads every neighbour of every cell.
+#[derive(Clone)]
+struct Grid {
+    cells: Vec<bool>,
+    width: usize,
+    height: usize,
+}
+impl Grid {
+    fn new(width: usize, height: usize, value: bool) -> Self {
+        Self {
+            cells: vec![value; width * height],
+            width,
+            height,
+        }
+    }
+    fn get(&self, x: usize, y: usize) -> bool {
+        self.cells[x + y * self.width]
+    }
+    fn set(&mut self, x: usize, y: usize, value: bool) {
+        self.cells[x + y * self.width] = value;
+    }
+    fn count_walls_around(&self, x: usize, y: usize) -> usize {
+        let mut count = 0;
+        for ny in (y as isize - 1)..=(y as isize + 1) {
+            for nx in (x as isize - 1)..=(x as isize + 1) {
+                let inside = (0..self.width as isize).contains(&nx)
+                    && (0..self.height as isize).contains(&ny);
+                if !inside || self.get(nx as usize, ny as usize) {
+                    count += 1;
+                }
+            }
+        }
+        count
+    }
+    fn to_bitset(&self) -> BitSet2d {
+        let mut bits = BitSet2d::new();
+        for y in 0..self.height {
+            for x in 0..self.width {
+                if self.get(x, y) {
+                    bits.insert((x as isize, y as isize));
+                }
+            }
+        }
+        bits
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+
+    use super::*;
+
+    const WIDTH: usize = 80;
+    const HEIGHT: usize = 50;
+
+    fn generators() -> [fn(&mut rand::rngs::StdRng) -> BitSet2d; 4] {
+        [
+            |rng| cellular_cave(rng, WIDTH, HEIGHT, 0.45, 4),
+            |rng| drunkards_walk(rng, WIDTH, HEIGHT, 0.4),
+            |rng| bsp_dungeon(rng, WIDTH, HEIGHT, 8),
+            |rng| value_noise(rng, WIDTH, HEIGHT, 8, 0.45),
+        ]
+    }
+
+    fn open_cells(map: &BitSet2d) -> Vec<(isize, isize)> {
+        let mut cells = Vec::new();
+        for y in 0..HEIGHT as isize {
+            for x in 0..WIDTH as isize {
+                if !map.contains((x, y)) {
+                    cells.push((x, y));
+                }
+            }
+        }
+        cells
+    }
+
+    #[test]
+    fn test_reproducible_with_solid_border() {
+        for generate in generators() {
+            let map = generate(&mut rand::rngs::StdRng::seed_from_u64(7));
+            assert_eq!(map, generate(&mut rand::rngs::StdRng::seed_from_u64(7)));
+            assert_ne!(map, generate(&mut rand::rngs::StdRng::seed_from_u64(8)));
+
+            for x in 0..WIDTH as isize {
+                assert!(map.contains((x, 0)) && map.contains((x, HEIGHT as isize - 1)));
+            }
+            for y in 0..HEIGHT as isize {
+                assert!(map.contains((0, y)) && map.contains((WIDTH as isize - 1, y)));
+            }
+            assert!(!open_cells(&map).is_empty());
+        }
+    }
+
+    #[test]
+    fn test_drunkards_walk_floor_fraction() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+        let map = drunkards_walk(&mut rng, WIDTH, HEIGHT, 0.25);
+        let interior = (WIDTH - 2) * (HEIGHT - 2);
+        assert_eq!(open_cells(&map).len(), interior / 4);
+    }
+
+    #[test]
+    fn test_bsp_dungeon_connected() {
+        for seed in 0..20 {
+            let map = bsp_dungeon(
+                &mut rand::rngs::StdRng::seed_from_u64(seed),
+                WIDTH,
+                HEIGHT,
+                6,
+            );
+            let open = open_cells(&map);
+
+            let mut reached = BitSet2d::new();
+            let mut stack = vec![open[0]];
+            while let Some((x, y)) = stack.pop() {
+                if map.contains((x, y)) || reached.contains((x, y)) {
+                    continue;
+                }
+                reached.insert((x, y));
+                stack.extend([(x + 1, y), (x - 1, y), (x, y + 1), (x, y - 1)]);
+            }
+            assert_eq!(reached.iter().count(), open.len(), "seed={seed}");
+        }
+    }
+}
@@ -1,17 +1,409 @@
 use core::ops::{Index, IndexMut};
 
-use glam::Vec2;
+use alloc::vec::Vec;
+use glam::{Affine2, Mat3, Vec2, Vec3};
+use libm::sqrtf;
+use rand::Rng;
 
-use crate::triangle::Triangle2;
+use crate::{
+    aabb::Aabb2,
+    circle::Circle,
+    clipping::{clip_polygon, clip_polygon_to_aabb},
+    convex_polygon::ConvexPolygon2,
+    offset::{offset_ring, Join},
+    polygon::Polygon2,
+    predicates::{orient2d, segments_cross_of, Orientation, Point},
+    raster::Spans,
+    segment::{raycast_edges, RayHit, Segment2},
+    triangle::{closest_point_on_edges, Triangle2},
+};
 
+/// Relative error allowed when checking a quad is cyclic or tangential.
+const CIRCLE_TOLERANCE: f32 = 1e-4;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct Quad2(pub [Vec2; 4]);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum QuadKind {
+    /// Every corner turns the same way, including degenerate quads where some
+    /// corners are straight.
+    Convex,
+    /// Simple, with the given reflex vertex.
+    Concave(usize),
+    /// Self-intersecting, where opposite edges cross to form a bow-tie.
+    Complex,
+}
+
+/// `Quad2::kind` for either precision.
+pub(crate) fn quad_kind<P: Point>(vertices: [P; 4]) -> QuadKind {
+    let [v1, v2, v3, v4] = vertices;
+    if segments_cross_of(v1, v2, v3, v4) || segments_cross_of(v2, v3, v4, v1) {
+        return QuadKind::Complex;
+    }
+    let turns: [Orientation; 4] = core::array::from_fn(|i| {
+        P::orient2d(vertices[(i + 3) & 3], vertices[i], vertices[(i + 1) & 3])
+    });
+    let count = |o| turns.iter().filter(|turn| **turn == o).count();
+    let (positive, negative) = (count(Orientation::Positive), count(Orientation::Negative));
+    if positive == 0 || negative == 0 {
+        return QuadKind::Convex;
+    }
+    // A simple quad turns the same way as its winding at all but one corner.
+    let reflex = if positive > negative {
+        Orientation::Negative
+    } else {
+        Orientation::Positive
+    };
+    QuadKind::Concave(turns.iter().position(|turn| *turn == reflex).unwrap_or(0))
+}
+
+/// `Quad2::triangles` for either precision.
+pub(crate) fn quad_triangles<P: Point>(vertices: [P; 4]) -> [[P; 3]; 2] {
+    let rotated = |i: usize| [0, 1, 2, 3].map(|offset| vertices[(i + offset) & 3]);
+    let split_at = |i| {
+        let [a, b, c, d] = rotated(i);
+        [[a, b, c], [c, d, a]]
+    };
+    match quad_kind(vertices) {
+        QuadKind::Convex => split_at(0),
+        QuadKind::Concave(reflex) => split_at(reflex),
+        QuadKind::Complex => {
+            let (i, t) = quad_crossing(vertices);
+            let [a, b, c, d] = rotated(i);
+            let x = a.lerp(b, t);
+            [[x, b, c], [x, d, a]]
+        }
+    }
+}
+
+/// For bow-ties, the edge `i` which crosses edge `i + 2`, and how far along
+/// edge `i` they cross.
+pub(crate) fn quad_crossing<P: Point>(vertices: [P; 4]) -> (usize, P::Scalar) {
+    let [v1, v2, v3, v4] = vertices;
+    let i = if segments_cross_of(v1, v2, v3, v4) {
+        0
+    } else {
+        1
+    };
+    let [a, b, c, d] = [0, 1, 2, 3].map(|offset| vertices[(i + offset) & 3]);
+    (i, P::crossing(a, b, c, d))
+}
+
 impl Quad2 {
+    /// Corners in the order `min`, `(max.x, min.y)`, `max`, `(min.x, max.y)`.
+    pub fn from_aabb(aabb: &Aabb2) -> Self {
+        let Aabb2 { min, max } = *aabb;
+        Quad2([min, Vec2::new(max.x, min.y), max, Vec2::new(min.x, max.y)])
+    }
+    /// A rectangle rotated by `angle` radians about its centre, with corners
+    /// in the same order as `from_aabb`.
+    pub fn from_rect_rotated(center: Vec2, half_extents: Vec2, angle: f32) -> Self {
+        let transform = Affine2::from_angle_translation(angle, center);
+        Quad2::from_aabb(&Aabb2::from_center_half_extents(Vec2::ZERO, half_extents))
+            .transform(&transform)
+    }
+
+    /// Points on the edges count as inside. Bow-ties follow the even-odd rule,
+    /// so both lobes are inside.
     pub fn contains(&self, pt: Vec2) -> bool {
+        let [t1, t2] = self.triangles();
+        t1.contains(pt) || t2.contains(pt)
+    }
+
+    pub fn kind(&self) -> QuadKind {
+        quad_kind(self.0)
+    }
+
+    /// Two triangles covering the quad, split along the diagonal from any
+    /// reflex vertex, or the two lobes of a bow-tie.
+    pub fn triangles(&self) -> [Triangle2; 2] {
+        quad_triangles(self.0).map(Triangle2)
+    }
+
+    /// Positive for `Orientation::Positive` windings. This is the net area,
+    /// where the opposite lobes of a bow-tie cancel out.
+    pub fn signed_area(&self) -> f32 {
+        let [v1, v2, v3, v4] = self.0;
+        0.5 * (v3 - v1).perp_dot(v4 - v2)
+    }
+    /// The area covered, including both lobes of a bow-tie.
+    pub fn area(&self) -> f32 {
+        match self.kind() {
+            QuadKind::Complex => self.triangles().iter().map(Triangle2::area).sum(),
+            _ => self.signed_area().abs(),
+        }
+    }
+    /// The winding of the quad as a whole, from the sign of its area.
+    pub fn orientation(&self) -> Orientation {
+        let area = self.signed_area();
+        if area > 0.0 {
+            Orientation::Positive
+        } else if area < 0.0 {
+            Orientation::Negative
+        } else {
+            Orientation::Collinear
+        }
+    }
+    /// The centre of mass, or the average vertex if the quad has no area.
+    pub fn centroid(&self) -> Vec2 {
+        let [v1, v2, v3, v4] = self.0;
+        let (t1, t2) = (Triangle2([v1, v2, v3]), Triangle2([v1, v3, v4]));
+        let (a1, a2) = (t1.signed_area(), t2.signed_area());
+        if a1 + a2 == 0.0 {
+            return (v1 + v2 + v3 + v4) / 4.0;
+        }
+        (t1.centroid() * a1 + t2.centroid() * a2) / (a1 + a2)
+    }
+    pub fn perimeter(&self) -> f32 {
+        self.edges().iter().map(Segment2::length).sum()
+    }
+    pub fn aabb(&self) -> Aabb2 {
+        Aabb2::from_points(self.0)
+    }
+
+    /// The edges, with edge `i` running from vertex `i` to vertex `i + 1`.
+    pub fn edges(&self) -> [Segment2; 4] {
+        let [v1, v2, v3, v4] = self.0;
+        [
+            Segment2([v1, v2]),
+            Segment2([v2, v3]),
+            Segment2([v3, v4]),
+            Segment2([v4, v1]),
+        ]
+    }
+    /// The part inside `clip`, as one piece for convex quads or up to two
+    /// otherwise.
+    pub fn clip(&self, clip: &ConvexPolygon2) -> Vec<ConvexPolygon2> {
+        self.convex_pieces()
+            .filter_map(|piece| ConvexPolygon2::convex_hull(clip_polygon(&piece, clip)))
+            .collect()
+    }
+    pub fn clip_to_aabb(&self, aabb: &Aabb2) -> Vec<ConvexPolygon2> {
+        self.convex_pieces()
+            .filter_map(|piece| ConvexPolygon2::convex_hull(clip_polygon_to_aabb(&piece, aabb)))
+            .collect()
+    }
+
+    /// As `ConvexPolygon2::offset`, as convex pieces. Concave quads give
+    /// nothing if the outline would fold over itself, as when growing well
+    /// past the depth of the notch, and bow-ties are offset lobe by lobe.
+    pub fn offset(&self, distance: f32, join: Join) -> Vec<ConvexPolygon2> {
+        match self.kind() {
+            QuadKind::Convex => ConvexPolygon2::new(self.0.to_vec())
+                .and_then(|polygon| polygon.offset(distance, join))
+                .into_iter()
+                .collect(),
+            QuadKind::Concave(_) => Polygon2::new(self.0.to_vec())
+                .and_then(|polygon| offset_ring(polygon.outline(), distance, join))
+                .and_then(Polygon2::new)
+                .and_then(|polygon| polygon.convex_decomposition())
+                .unwrap_or_default(),
+            QuadKind::Complex => self
+                .triangles()
+                .iter()
+                .filter_map(|triangle| triangle.offset(distance, join))
+                .collect(),
+        }
+    }
+
+    fn convex_pieces(&self) -> impl Iterator<Item = Vec<Vec2>> {
+        let pieces = if self.is_strictly_convex() {
+            [Some(self.0.to_vec()), None]
+        } else {
+            self.triangles().map(|triangle| Some(triangle.0.to_vec()))
+        };
+        pieces.into_iter().flatten()
+    }
+
+    pub fn closest_point_on_boundary(&self, pt: Vec2) -> Vec2 {
+        closest_point_on_edges(&self.edges(), pt)
+    }
+
+    /// The first point along the ray inside the shape, where `distance` is in
+    /// multiples of `direction`.
+    pub fn raycast(&self, origin: Vec2, direction: Vec2, max_distance: f32) -> Option<RayHit> {
+        if self.contains(origin) {
+            return Some(RayHit {
+                distance: 0.0,
+                point: origin,
+                normal: Vec2::ZERO,
+            });
+        }
+        raycast_edges(&self.edges(), origin, direction, max_distance)
+    }
+    /// Casts from the segment's start to its end, so `distance` is in `0..=1`.
+    pub fn cast_segment(&self, segment: &Segment2) -> Option<RayHit> {
+        self.raycast(segment[0], segment.direction(), 1.0)
+    }
+
+    /// The circle through all four vertices, if the quad is cyclic.
+    pub fn circumcircle(&self) -> Option<Circle> {
         let [v1, v2, v3, v4] = self.0;
-        Triangle2([v1, v2, v3]).contains(pt) || Triangle2([v3, v4, v1]).contains(pt)
+        let circle = Triangle2([v1, v2, v3]).circumcircle()?;
+        let error = (v4.distance(circle.center) - circle.radius).abs();
+        (error <= circle.radius * CIRCLE_TOLERANCE).then_some(circle)
+    }
+    /// The circle touching all four edges, if the quad is convex and tangential.
+    pub fn incircle(&self) -> Option<Circle> {
+        let [v1, v2, v3, v4] = self.0;
+        let bisector = |prev: Vec2, v: Vec2, next: Vec2| {
+            (prev - v).normalize_or_zero() + (next - v).normalize_or_zero()
+        };
+        let d1 = bisector(v4, v1, v2);
+        let d2 = bisector(v1, v2, v3);
+        let denom = d1.perp_dot(d2);
+        if denom == 0.0 {
+            return None;
+        }
+        let center = v1 + d1 * ((v2 - v1).perp_dot(d2) / denom);
+
+        let distances = self.edges().map(|edge| {
+            let dir = edge.direction();
+            dir.perp_dot(center - edge[0]).abs() / dir.length()
+        });
+        let radius = distances[0];
+        let tangential = distances
+            .iter()
+            .all(|d| (d - radius).abs() <= radius * CIRCLE_TOLERANCE);
+        (tangential && radius > 0.0 && self.is_strictly_convex())
+            .then_some(Circle::new(center, radius))
+    }
+
+    /// Whether `kind` is `QuadKind::Convex`, including degenerate quads.
+    pub fn is_convex(&self) -> bool {
+        self.kind() == QuadKind::Convex
+    }
+    /// Whether every corner turns the same way, excluding degenerate quads
+    /// with straight corners.
+    pub fn is_strictly_convex(&self) -> bool {
+        let turns: [Orientation; 4] =
+            core::array::from_fn(|i| orient2d(self[(i + 3) & 3], self[i], self[(i + 1) & 3]));
+        turns[0] != Orientation::Collinear && turns.iter().all(|turn| *turn == turns[0])
+    }
+
+    // The mappings take the unit square's `(0, 0)`, `(1, 0)`, `(1, 1)` and
+    // `(0, 1)` corners to `v1`, `v2`, `v3` and `v4`.
+
+    pub fn bilinear(&self, uv: Vec2) -> Vec2 {
+        let [v1, v2, v3, v4] = self.0;
+        let top = v1.lerp(v2, uv.x);
+        let bottom = v4.lerp(v3, uv.x);
+        top.lerp(bottom, uv.y)
+    }
+
+    /// The `uv` that `bilinear` maps to `pt`, preferring one inside the unit
+    /// square. Anything outside `0..=1` means `pt` is outside the quad.
+    pub fn inverse_bilinear(&self, pt: Vec2) -> Option<Vec2> {
+        let [v1, v2, v3, v4] = self.0;
+        let (e, f, g, h) = (v2 - v1, v4 - v1, v1 - v2 + v3 - v4, pt - v1);
+        let k2 = g.perp_dot(f);
+        let k1 = e.perp_dot(f) + h.perp_dot(g);
+        let k0 = h.perp_dot(e);
+
+        let u_for = |v: f32| {
+            let along = e + g * v;
+            let len_sq = along.length_squared();
+            (len_sq != 0.0).then(|| Vec2::new((h - f * v).dot(along) / len_sq, v))
+        };
+
+        if k2.abs() <= f32::EPSILON * k1.abs() {
+            // Opposite edges are parallel, so the quadratic is linear.
+            return if k1 == 0.0 { None } else { u_for(-k0 / k1) };
+        }
+        let discriminant = k1 * k1 - 4.0 * k0 * k2;
+        if discriminant < 0.0 {
+            return None;
+        }
+        let w = sqrtf(discriminant);
+        let first = u_for((-k1 - w) / (2.0 * k2));
+        let inside = |uv: &Vec2| uv.cmpge(Vec2::ZERO).all() && uv.cmple(Vec2::ONE).all();
+        match first {
+            Some(uv) if inside(&uv) => Some(uv),
+            _ => u_for((-k1 + w) / (2.0 * k2)).or(first),
+        }
+    }
+
+    /// The homography taking `(u, v, 1)` to homogeneous points in the quad, or
+    /// `None` if three of the vertices are collinear.
+    pub fn homography(&self) -> Option<Mat3> {
+        if (0..4).any(|i| {
+            orient2d(self[i], self[(i + 1) & 3], self[(i + 2) & 3]) == Orientation::Collinear
+        }) {
+            return None;
+        }
+        let [v1, v2, v3, v4] = self.0;
+        let sum = v1 - v2 + v3 - v4;
+        let (d1, d2) = (v2 - v3, v4 - v3);
+        let den = d1.perp_dot(d2);
+        if den == 0.0 {
+            return None;
+        }
+        let g = sum.perp_dot(d2) / den;
+        let h = d1.perp_dot(sum) / den;
+        Some(Mat3::from_cols(
+            (v2 - v1 + v2 * g).extend(g),
+            (v4 - v1 + v4 * h).extend(h),
+            v1.extend(1.0),
+        ))
+    }
+
+    /// Maps with a perspective warp, as a textured quad drawn in 3D would appear.
+    pub fn projective(&self, uv: Vec2) -> Option<Vec2> {
+        project(self.homography()?, uv)
+    }
+    pub fn inverse_projective(&self, pt: Vec2) -> Option<Vec2> {
+        let homography = self.homography()?;
+        if homography.determinant() == 0.0 {
+            return None;
+        }
+        project(homography.inverse(), pt)
+    }
+
+    /// A uniformly distributed point inside, picking between the two
+    /// triangles by area.
+    pub fn sample(&self, rng: &mut impl Rng) -> Vec2 {
+        let [t1, t2] = self.triangles();
+        let (a1, a2) = (t1.area(), t2.area());
+        if rng.gen::<f32>() * (a1 + a2) < a1 {
+            t1.sample(rng)
+        } else {
+            t2.sample(rng)
+        }
+    }
+
+    /// The pixel rows covered under the top-left rule.
+    pub fn spans(&self) -> Spans {
+        Spans::new(self.triangles().map(Some), self.aabb())
+    }
+
+    pub fn transform(&self, transform: &Affine2) -> Self {
+        Quad2(self.0.map(|v| transform.transform_point2(v)))
+    }
+    /// Applies a homogeneous 2D transform, which may include perspective, or
+    /// `None` if a vertex maps to infinity.
+    pub fn transform_mat3(&self, matrix: &Mat3) -> Option<Self> {
+        let [v1, v2, v3, v4] = self.0.map(|v| project(*matrix, v));
+        Some(Quad2([v1?, v2?, v3?, v4?]))
+    }
+    pub fn translate(&self, offset: Vec2) -> Self {
+        Quad2(self.0.map(|v| v + offset))
+    }
+    /// Rotates by `angle` radians about `pivot`.
+    pub fn rotate_about(&self, pivot: Vec2, angle: f32) -> Self {
+        let rotation = Vec2::from_angle(angle);
+        Quad2(self.0.map(|v| pivot + rotation.rotate(v - pivot)))
+    }
+    pub fn scale_about(&self, pivot: Vec2, scale: Vec2) -> Self {
+        Quad2(self.0.map(|v| pivot + (v - pivot) * scale))
     }
 }
 
+pub(crate) fn project(matrix: Mat3, pt: Vec2) -> Option<Vec2> {
+    let Vec3 { x, y, z } = matrix * pt.extend(1.0);
+    (z != 0.0).then(|| Vec2::new(x, y) / z)
+}
+
 impl Index<usize> for Quad2 {
     type Output = Vec2;
     fn index(&self, index: usize) -> &Self::Output {
@@ -27,9 +419,20 @@ impl IndexMut<usize> for Quad2 {
 #[cfg(feature = "macroquad-render")]
 impl Quad2 {
     pub fn draw(&self, color: macroquad::prelude::Color) {
-        let [v1, v2, v3, v4] = self.0;
-        macroquad::shapes::draw_triangle(v1, v2, v3, color);
-        macroquad::shapes::draw_triangle(v3, v4, v1, color);
+        for triangle in self.triangles() {
+            triangle.draw(color);
+        }
+    }
+    /// Draws with per-vertex texture coordinates and tints.
+    pub fn draw_textured(
+        &self,
+        texture: macroquad::texture::Texture2D,
+        uvs: [Vec2; 4],
+        colors: [macroquad::prelude::Color; 4],
+    ) {
+        let mut batch = crate::mesh_batch::MeshBatch::new(Some(texture));
+        batch.push_quad(self, uvs, colors);
+        batch.draw();
     }
     pub fn draw_lines(&self, thickness: f32, color: macroquad::prelude::Color) {
         for i in 0..4 {
@@ -39,3 +442,299 @@ impl Quad2 {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_measurements() {
+        let square = Quad2([
+            Vec2::new(0.0, 0.0),
+            Vec2::new(2.0, 0.0),
+            Vec2::new(2.0, 2.0),
+            Vec2::new(0.0, 2.0),
+        ]);
+        assert_eq!(square.signed_area(), 4.0);
+        assert_eq!(square.orientation(), Orientation::Positive);
+        assert_eq!(square.perimeter(), 8.0);
+        assert_eq!(square.centroid(), Vec2::ONE);
+        assert_eq!(square.aabb(), Aabb2::new(Vec2::ZERO, Vec2::splat(2.0)));
+        assert_eq!(
+            square.closest_point_on_boundary(Vec2::new(1.5, 1.0)),
+            Vec2::new(2.0, 1.0)
+        );
+
+        let circumcircle = square.circumcircle().unwrap();
+        assert_eq!(circumcircle.center, Vec2::ONE);
+        assert!((circumcircle.radius - 2f32.sqrt()).abs() < 1e-6);
+        assert_eq!(square.incircle(), Some(Circle::new(Vec2::ONE, 1.0)));
+
+        // A concave kite, which has a circle touching all four edge lines.
+        let dart = Quad2([
+            Vec2::new(0.0, 0.0),
+            Vec2::new(4.0, 0.0),
+            Vec2::new(1.0, 1.0),
+            Vec2::new(0.0, 4.0),
+        ]);
+        assert_eq!(dart.signed_area(), 4.0);
+        assert_eq!(dart.circumcircle(), None);
+        assert_eq!(dart.incircle(), None);
+        assert_eq!(dart.centroid(), Vec2::ONE);
+        assert!(!dart.is_convex());
+        assert!(square.is_convex());
+        assert!(square.is_strictly_convex());
+    }
+
+    #[test]
+    fn test_kind_and_contains() {
+        // Reflex vertex at v2, which the v1-v3 diagonal would cut through.
+        let arrow = Quad2([
+            Vec2::new(0.0, 0.0),
+            Vec2::new(2.0, 1.0),
+            Vec2::new(4.0, 0.0),
+            Vec2::new(2.0, 4.0),
+        ]);
+        assert_eq!(arrow.kind(), QuadKind::Concave(1));
+        assert!(arrow.contains(Vec2::new(2.0, 2.0)));
+        assert!(!arrow.contains(Vec2::new(2.0, 0.5)));
+        assert!(!arrow.contains(Vec2::new(1.0, 0.1)));
+        assert_eq!(
+            arrow.triangles().iter().map(Triangle2::area).sum::<f32>(),
+            arrow.area()
+        );
+
+        let bow_tie = Quad2([
+            Vec2::new(0.0, 0.0),
+            Vec2::new(2.0, 2.0),
+            Vec2::new(2.0, 0.0),
+            Vec2::new(0.0, 2.0),
+        ]);
+        assert_eq!(bow_tie.kind(), QuadKind::Complex);
+        assert_eq!(bow_tie.signed_area(), 0.0);
+        assert_eq!(bow_tie.area(), 2.0);
+        assert!(bow_tie.contains(Vec2::new(0.2, 1.0)));
+        assert!(bow_tie.contains(Vec2::new(1.8, 1.0)));
+        assert!(!bow_tie.contains(Vec2::new(1.0, 0.2)));
+        assert!(!bow_tie.contains(Vec2::new(1.0, 1.8)));
+
+        let square = Quad2([Vec2::ZERO, Vec2::X, Vec2::ONE, Vec2::Y]);
+        assert_eq!(square.kind(), QuadKind::Convex);
+        assert!(square.contains(Vec2::splat(0.5)));
+
+        // A triangle with a vertex partway along one edge.
+        let straight = Quad2([Vec2::ZERO, Vec2::X, Vec2::new(2.0, 0.0), Vec2::Y]);
+        assert_eq!(straight.kind(), QuadKind::Convex);
+        assert!(straight.is_convex());
+        assert!(!straight.is_strictly_convex());
+        let flat = Quad2([
+            Vec2::ZERO,
+            Vec2::X,
+            Vec2::new(2.0, 0.0),
+            Vec2::new(3.0, 0.0),
+        ]);
+        assert_eq!(flat.kind(), QuadKind::Convex);
+        assert!(flat.is_convex());
+        assert!(!flat.is_strictly_convex());
+    }
+
+    #[test]
+    fn test_mappings() {
+        let sut = Quad2([
+            Vec2::new(1.0, 1.0),
+            Vec2::new(5.0, 0.0),
+            Vec2::new(6.0, 4.0),
+            Vec2::new(0.0, 3.0),
+        ]);
+        let corners = [Vec2::ZERO, Vec2::X, Vec2::ONE, Vec2::Y];
+        for (corner, vertex) in corners.into_iter().zip(sut.0) {
+            assert!(sut.bilinear(corner).abs_diff_eq(vertex, 1e-5));
+            assert!(sut.projective(corner).unwrap().abs_diff_eq(vertex, 1e-5));
+        }
+
+        for uv in [
+            Vec2::new(0.25, 0.75),
+            Vec2::new(0.5, 0.5),
+            Vec2::new(0.9, 0.1),
+        ] {
+            let pt = sut.bilinear(uv);
+            assert!(
+                sut.inverse_bilinear(pt).unwrap().abs_diff_eq(uv, 1e-5),
+                "{uv:?}"
+            );
+            let pt = sut.projective(uv).unwrap();
+            assert!(
+                sut.inverse_projective(pt).unwrap().abs_diff_eq(uv, 1e-5),
+                "{uv:?}"
+            );
+        }
+        let centre = Vec2::splat(0.5);
+        assert!(!sut
+            .bilinear(centre)
+            .abs_diff_eq(sut.projective(centre).unwrap(), 1e-3));
+
+        let outside = sut.inverse_bilinear(Vec2::new(-5.0, 2.0)).unwrap();
+        assert!(outside.x < 0.0);
+
+        // Parallelograms take the linear path.
+        let parallelogram = Quad2([Vec2::ZERO, Vec2::X * 2.0, Vec2::new(3.0, 1.0), Vec2::ONE]);
+        let uv = parallelogram.inverse_bilinear(Vec2::new(1.5, 0.5)).unwrap();
+        assert!(uv.abs_diff_eq(Vec2::new(0.5, 0.5), 1e-6));
+    }
+
+    #[test]
+    fn test_transforms() {
+        let sut = Quad2::from_rect_rotated(
+            Vec2::new(5.0, 5.0),
+            Vec2::new(2.0, 1.0),
+            core::f32::consts::FRAC_PI_2,
+        );
+        let expected = [
+            Vec2::new(6.0, 3.0),
+            Vec2::new(6.0, 7.0),
+            Vec2::new(4.0, 7.0),
+            Vec2::new(4.0, 3.0),
+        ];
+        for (actual, expected) in sut.0.iter().zip(expected) {
+            assert!(actual.abs_diff_eq(expected, 1e-5), "{sut:?}");
+        }
+        assert!((sut.area() - 8.0).abs() < 1e-5);
+
+        let unit = Quad2::from_aabb(&Aabb2::new(Vec2::ZERO, Vec2::ONE));
+        let moved = unit
+            .scale_about(Vec2::ZERO, Vec2::new(2.0, 3.0))
+            .translate(Vec2::X);
+        assert_eq!(
+            moved,
+            Quad2::from_aabb(&Aabb2::new(Vec2::X, Vec2::new(3.0, 3.0)))
+        );
+        let affine = Affine2::from_scale_angle_translation(Vec2::new(2.0, 3.0), 0.0, Vec2::X);
+        assert_eq!(unit.transform(&affine), moved);
+        assert_eq!(unit.transform_mat3(&Mat3::from(affine)), Some(moved));
+
+        // Mapping the unit square through a quad's homography gives the quad.
+        let warped = Quad2([
+            Vec2::ZERO,
+            Vec2::X * 4.0,
+            Vec2::new(3.0, 2.0),
+            Vec2::Y * 2.0,
+        ]);
+        let projected = unit.transform_mat3(&warped.homography().unwrap()).unwrap();
+        for (actual, expected) in projected.0.iter().zip(warped.0) {
+            assert!(actual.abs_diff_eq(expected, 1e-5));
+        }
+
+        // Any three collinear vertices, not just v2, v3 and v4.
+        let straight = Quad2([Vec2::ZERO, Vec2::X, Vec2::new(2.0, 0.0), Vec2::Y]);
+        for i in 0..4 {
+            let rotated = Quad2(core::array::from_fn(|j| straight[(i + j) & 3]));
+            assert_eq!(rotated.homography(), None, "{rotated:?}");
+            assert_eq!(rotated.projective(Vec2::splat(0.5)), None);
+        }
+    }
+
+    #[test]
+    fn test_raycast() {
+        // An arrowhead pointing up, with its notch at (2, 1).
+        let sut = Quad2([
+            Vec2::new(0.0, 0.0),
+            Vec2::new(2.0, 1.0),
+            Vec2::new(4.0, 0.0),
+            Vec2::new(2.0, 4.0),
+        ]);
+        let hit = sut.raycast(Vec2::new(2.0, -1.0), Vec2::Y, 10.0).unwrap();
+        assert_eq!(hit.distance, 2.0);
+        assert_eq!(hit.point, Vec2::new(2.0, 1.0));
+
+        let hit = sut.raycast(Vec2::new(1.0, -1.0), Vec2::Y, 10.0).unwrap();
+        assert_eq!(hit.point, Vec2::new(1.0, 0.5));
+        assert!(hit
+            .normal
+            .abs_diff_eq(Vec2::new(1.0, -2.0).normalize(), 1e-6));
+
+        let miss = Segment2([Vec2::new(5.0, 0.0), Vec2::new(5.0, 4.0)]);
+        assert_eq!(sut.cast_segment(&miss), None);
+    }
+
+    #[test]
+    fn test_clip() {
+        let screen = Aabb2::new(Vec2::ZERO, Vec2::splat(10.0));
+        let convex = Quad2::from_rect_rotated(Vec2::new(10.0, 5.0), Vec2::splat(2.0), 0.0);
+        let pieces = convex.clip_to_aabb(&screen);
+        assert_eq!(pieces.len(), 1);
+        assert!((pieces[0].area() - 8.0).abs() < 1e-5);
+
+        let arrow = Quad2([
+            Vec2::new(0.0, 0.0),
+            Vec2::new(2.0, 1.0),
+            Vec2::new(4.0, 0.0),
+            Vec2::new(2.0, 4.0),
+        ]);
+        let lower = Aabb2::new(Vec2::splat(-1.0), Vec2::new(5.0, 0.5));
+        let pieces = arrow.clip_to_aabb(&lower);
+        // The notch splits the bottom strip into the two barbs.
+        assert_eq!(pieces.len(), 2);
+        assert!(pieces
+            .iter()
+            .all(|piece| !piece.contains(Vec2::new(2.0, 0.25))));
+        let area: f32 = pieces.iter().map(ConvexPolygon2::area).sum();
+        assert!((area - 0.375).abs() < 1e-5, "{area}");
+
+        let clip = ConvexPolygon2::convex_hull([
+            Vec2::new(-1.0, -1.0),
+            Vec2::new(5.0, -1.0),
+            Vec2::new(5.0, 0.5),
+            Vec2::new(-1.0, 0.5),
+        ])
+        .unwrap();
+        let clipped: f32 = arrow.clip(&clip).iter().map(ConvexPolygon2::area).sum();
+        assert!((clipped - area).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_offset() {
+        let arrow = Quad2([
+            Vec2::new(0.0, 0.0),
+            Vec2::new(2.0, 1.0),
+            Vec2::new(4.0, 0.0),
+            Vec2::new(2.0, 4.0),
+        ]);
+        let boundary_distance = |pt: Vec2| {
+            arrow
+                .edges()
+                .iter()
+                .map(|edge| edge.distance(pt))
+                .fold(f32::MAX, f32::min)
+        };
+        for distance in [0.1, -0.1] {
+            let pieces = arrow.offset(distance, Join::Round { tolerance: 1e-3 });
+            assert!(!pieces.is_empty());
+            for &v in pieces.iter().flat_map(ConvexPolygon2::vertices) {
+                assert!((boundary_distance(v) - 0.1).abs() < 2e-3, "{distance} {v}");
+                assert_eq!(arrow.contains(v), distance < 0.0);
+            }
+        }
+        let area: f32 = arrow
+            .offset(0.1, Join::Miter { limit: 10.0 })
+            .iter()
+            .map(ConvexPolygon2::area)
+            .sum();
+        assert!(area > arrow.area() + 0.1 * arrow.perimeter());
+
+        // Grown far enough, the notch's edges fold over each other.
+        assert!(arrow
+            .offset(5.0, Join::Round { tolerance: 1e-2 })
+            .is_empty());
+
+        let square = Quad2::from_aabb(&Aabb2::new(Vec2::ZERO, Vec2::splat(2.0)));
+        let grown = square.offset(1.0, Join::Miter { limit: 2.0 });
+        assert!((grown[0].area() - 16.0).abs() < 1e-5);
+        let bow_tie = Quad2([
+            Vec2::ZERO,
+            Vec2::new(2.0, 2.0),
+            Vec2::new(2.0, 0.0),
+            Vec2::new(0.0, 2.0),
+        ]);
+        assert_eq!(bow_tie.offset(0.1, Join::Miter { limit: 2.0 }).len(), 2);
+    }
+}
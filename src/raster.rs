@@ -0,0 +1,173 @@
+use core::ops::Range;
+
+use glam::Vec2;
+use libm::{ceilf, floorf};
+
+use crate::{
+    aabb::Aabb2,
+    triangle::{Boundary, Triangle2},
+};
+
+/// Rows of pixels covered by a shape, as `(y, x_start..x_end)`, top to bottom
+/// and left to right.
+///
+/// Pixel `(x, y)` is covered when its centre `(x + 0.5, y + 0.5)` is inside
+/// under the top-left rule, so shapes sharing an edge never both cover a
+/// pixel and never leave a gap. Empty spans are skipped.
+#[derive(Clone, Debug)]
+pub struct Spans {
+    triangles: [Option<Triangle2>; 2],
+    rows: Range<isize>,
+    /// A second span on the current row, where the triangles don't touch.
+    pending: Option<(isize, Range<isize>)>,
+}
+
+impl Spans {
+    pub(crate) fn new(triangles: [Option<Triangle2>; 2], aabb: Aabb2) -> Self {
+        let first = ceilf(aabb.min.y - 0.5) as isize;
+        let last = floorf(aabb.max.y - 0.5) as isize;
+        Self {
+            triangles,
+            rows: first..(last + 1).max(first),
+            pending: None,
+        }
+    }
+}
+
+impl Iterator for Spans {
+    type Item = (isize, Range<isize>);
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(span) = self.pending.take() {
+            return Some(span);
+        }
+        loop {
+            let y = self.rows.next()?;
+            let [a, b] = self
+                .triangles
+                .map(|triangle| triangle.map(|t| row_span(&t, y)).filter(|s| !s.is_empty()));
+            match (a, b) {
+                (None, None) => {}
+                (Some(span), None) | (None, Some(span)) => return Some((y, span)),
+                (Some(a), Some(b)) if a.start <= b.end && b.start <= a.end => {
+                    return Some((y, a.start.min(b.start)..a.end.max(b.end)));
+                }
+                (Some(a), Some(b)) => {
+                    let (left, right) = if a.start < b.start { (a, b) } else { (b, a) };
+                    self.pending = Some((y, right));
+                    return Some((y, left));
+                }
+            }
+        }
+    }
+}
+
+/// The covered pixels of row `y`, estimated from where the edges cross the
+/// row's centre line and then corrected with the exact containment test.
+fn row_span(triangle: &Triangle2, y: isize) -> Range<isize> {
+    let center_y = y as f32 + 0.5;
+    let (mut min_x, mut max_x) = (f32::INFINITY, f32::NEG_INFINITY);
+    for edge in triangle.edges() {
+        let [a, b] = edge.0;
+        if (a.y.min(b.y)..=a.y.max(b.y)).contains(&center_y) {
+            let x = if a.y == b.y {
+                // Horizontal edges lie on the line, so both ends count.
+                min_x = min_x.min(a.x.min(b.x));
+                a.x.max(b.x)
+            } else {
+                a.x + (b.x - a.x) * (center_y - a.y) / (b.y - a.y)
+            };
+            min_x = min_x.min(x);
+            max_x = max_x.max(x);
+        }
+    }
+    if min_x > max_x {
+        return 0..0;
+    }
+
+    let covered =
+        |x: isize| triangle.contains_with(Vec2::new(x as f32 + 0.5, center_y), Boundary::TopLeft);
+    let mut start = ceilf(min_x - 0.5) as isize;
+    let mut end = floorf(max_x - 0.5) as isize + 1;
+    while covered(start - 1) {
+        start -= 1;
+    }
+    while start < end && !covered(start) {
+        start += 1;
+    }
+    while covered(end) {
+        end += 1;
+    }
+    while end > start && !covered(end - 1) {
+        end -= 1;
+    }
+    start..end
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+    use rand::{Rng, SeedableRng};
+
+    use super::*;
+    use crate::quadrilateral::Quad2;
+
+    fn covered_pixels(spans: impl Iterator<Item = (isize, Range<isize>)>) -> Vec<(isize, isize)> {
+        spans.flat_map(|(y, xs)| xs.map(move |x| (x, y))).collect()
+    }
+
+    #[test]
+    fn test_matches_top_left_rule() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+        for _ in 0..200 {
+            // Snapping to quarter pixels puts plenty of centres on edges.
+            let mut vertex =
+                || Vec2::new(rng.gen_range(-40..40) as f32, rng.gen_range(-40..40) as f32) * 0.25;
+            let sut = Triangle2([vertex(), vertex(), vertex()]);
+
+            let mut expected = Vec::new();
+            for y in -12..12 {
+                for x in -12..12 {
+                    let center = Vec2::new(x as f32 + 0.5, y as f32 + 0.5);
+                    if sut.contains_with(center, Boundary::TopLeft) {
+                        expected.push((x, y));
+                    }
+                }
+            }
+            assert_eq!(covered_pixels(sut.spans()), expected, "{sut:?}");
+        }
+    }
+
+    #[test]
+    fn test_shared_edges_cover_once() {
+        let sut = Quad2([
+            Vec2::new(0.5, 0.5),
+            Vec2::new(7.5, 1.5),
+            Vec2::new(6.5, 6.5),
+            Vec2::new(1.5, 7.5),
+        ]);
+        let [t1, t2] = sut.triangles();
+        let mut separately = covered_pixels(t1.spans().chain(t2.spans()));
+        let together = covered_pixels(sut.spans());
+        separately.sort_by_key(|&(x, y)| (y, x));
+        assert_eq!(together, separately);
+
+        // Rows come out as single spans for convex quads.
+        let rows: Vec<_> = sut.spans().map(|(y, _)| y).collect();
+        assert!(rows.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    fn test_concave_quad_rows_split() {
+        // An arrowhead pointing up, whose notch splits the lowest rows.
+        let sut = Quad2([
+            Vec2::new(0.0, 0.0),
+            Vec2::new(4.0, 2.0),
+            Vec2::new(8.0, 0.0),
+            Vec2::new(4.0, 8.0),
+        ]);
+        let spans: Vec<_> = sut.spans().collect();
+        assert_eq!(spans[0], (0, 0..1));
+        assert_eq!(spans[1], (0, 7..8));
+        assert_eq!(spans.iter().filter(|(y, _)| *y == 4).count(), 1);
+    }
+}
@@ -0,0 +1,140 @@
+use alloc::vec::Vec;
+use glam::Vec2;
+use rand::Rng;
+
+use crate::triangle::Triangle2;
+
+/// Picks uniformly distributed points across a set of triangles, such as a
+/// triangulated emitter shape, weighting each triangle by its area.
+#[derive(Clone, Debug)]
+pub struct TriangleSampler {
+    triangles: Vec<Triangle2>,
+    /// Running total of the areas, for binary searching.
+    cumulative_areas: Vec<f32>,
+}
+impl TriangleSampler {
+    /// `None` if the triangles have no area between them.
+    pub fn new(triangles: impl IntoIterator<Item = Triangle2>) -> Option<Self> {
+        let triangles: Vec<Triangle2> = triangles.into_iter().collect();
+        let mut total = 0.0;
+        let cumulative_areas: Vec<f32> = triangles
+            .iter()
+            .map(|triangle| {
+                total += triangle.area();
+                total
+            })
+            .collect();
+        (total > 0.0).then_some(Self {
+            triangles,
+            cumulative_areas,
+        })
+    }
+
+    pub fn triangles(&self) -> &[Triangle2] {
+        &self.triangles
+    }
+    pub fn area(&self) -> f32 {
+        self.cumulative_areas.last().copied().unwrap_or(0.0)
+    }
+
+    pub fn sample(&self, rng: &mut impl Rng) -> Vec2 {
+        let target = rng.gen::<f32>() * self.area();
+        let index = self
+            .cumulative_areas
+            .partition_point(|&area| area <= target)
+            .min(self.triangles.len() - 1);
+        self.triangles[index].sample(rng)
+    }
+}
+
+/// Samples once from a slice, without keeping the areas around. Prefer
+/// `TriangleSampler` when sampling the same triangles repeatedly.
+pub fn sample_triangles(triangles: &[Triangle2], rng: &mut impl Rng) -> Option<Vec2> {
+    let total: f32 = triangles.iter().map(Triangle2::area).sum();
+    if total <= 0.0 {
+        return None;
+    }
+    let mut target = rng.gen::<f32>() * total;
+    for triangle in triangles {
+        target -= triangle.area();
+        if target < 0.0 {
+            return Some(triangle.sample(rng));
+        }
+    }
+    triangles
+        .iter()
+        .rev()
+        .find(|triangle| triangle.area() > 0.0)
+        .map(|triangle| triangle.sample(rng))
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+
+    use super::*;
+    use crate::quadrilateral::Quad2;
+
+    /// Counts samples in each cell of a grid over `0..size`, and checks each
+    /// count is near its expected share.
+    fn assert_uniform(samples: impl Iterator<Item = Vec2>, expected: impl Fn(usize, usize) -> f32) {
+        const CELLS: usize = 4;
+        let mut counts = [[0usize; CELLS]; CELLS];
+        let mut total = 0;
+        for pt in samples {
+            counts[(pt.y as usize).min(CELLS - 1)][(pt.x as usize).min(CELLS - 1)] += 1;
+            total += 1;
+        }
+        for (y, row) in counts.iter().enumerate() {
+            for (x, &count) in row.iter().enumerate() {
+                let share = count as f32 / total as f32;
+                assert!((share - expected(x, y)).abs() < 0.01, "{x},{y}: {share}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_triangle_sample() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+        let sut = Triangle2([Vec2::ZERO, Vec2::new(4.0, 0.0), Vec2::new(0.0, 4.0)]);
+        let samples: Vec<_> = (0..40_000).map(|_| sut.sample(&mut rng)).collect();
+        assert!(samples.iter().all(|&pt| sut.contains(pt)));
+        // Cells below the diagonal are full, those on it are half covered.
+        assert_uniform(samples.into_iter(), |x, y| match x + y {
+            0..=2 => 1.0 / 8.0,
+            3 => 0.5 / 8.0,
+            _ => 0.0,
+        });
+    }
+
+    #[test]
+    fn test_area_weighted() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(1);
+        // A 4x4 square split unevenly, which is biased if triangles are
+        // picked uniformly rather than by area.
+        let triangles = [
+            Triangle2([Vec2::ZERO, Vec2::new(4.0, 0.0), Vec2::new(1.0, 4.0)]),
+            Triangle2([Vec2::new(4.0, 0.0), Vec2::splat(4.0), Vec2::new(1.0, 4.0)]),
+            Triangle2([Vec2::ZERO, Vec2::new(1.0, 4.0), Vec2::new(0.0, 4.0)]),
+        ];
+        let sut = TriangleSampler::new(triangles).unwrap();
+        assert_eq!(sut.area(), 16.0);
+        let uniform = |_, _| 1.0 / 16.0;
+        assert_uniform((0..40_000).map(|_| sut.sample(&mut rng)), uniform);
+        assert_uniform(
+            (0..40_000).map(|_| sample_triangles(&triangles, &mut rng).unwrap()),
+            uniform,
+        );
+
+        let quad = Quad2([
+            Vec2::ZERO,
+            Vec2::new(4.0, 0.0),
+            Vec2::splat(4.0),
+            Vec2::new(0.0, 4.0),
+        ]);
+        assert_uniform((0..40_000).map(|_| quad.sample(&mut rng)), uniform);
+
+        assert!(TriangleSampler::new([Triangle2([Vec2::ZERO, Vec2::X, Vec2::X * 2.0])]).is_none());
+        assert_eq!(sample_triangles(&[], &mut rng), None);
+    }
+}
@@ -0,0 +1,221 @@
+use core::ops::{Index, IndexMut};
+
+use glam::Vec2;
+
+use crate::predicates::{orient2d, Orientation};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Segment2(pub [Vec2; 2]);
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SegmentIntersection {
+    Point(Vec2),
+    /// Collinear segments sharing more than one point.
+    Overlap(Segment2),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RayHit {
+    /// In multiples of the ray's direction.
+    pub distance: f32,
+    pub point: Vec2,
+    /// The unit normal of the edge that was hit, facing the ray, or zero if
+    /// the ray started inside the shape.
+    pub normal: Vec2,
+}
+impl Segment2 {
+    pub fn length(&self) -> f32 {
+        let [v1, v2] = self.0;
+        v1.distance(v2)
+    }
+    pub fn direction(&self) -> Vec2 {
+        let [v1, v2] = self.0;
+        v2 - v1
+    }
+
+    pub fn closest_point(&self, pt: Vec2) -> Vec2 {
+        let [v1, v2] = self.0;
+        let dir = v2 - v1;
+        let len_sq = dir.length_squared();
+        if len_sq == 0.0 {
+            return v1;
+        }
+        let t = ((pt - v1).dot(dir) / len_sq).clamp(0.0, 1.0);
+        v1 + dir * t
+    }
+    pub fn distance(&self, pt: Vec2) -> f32 {
+        self.closest_point(pt).distance(pt)
+    }
+
+    /// Whether the closed segments meet is decided exactly; only the returned
+    /// point is subject to rounding.
+    pub fn intersection(&self, other: &Segment2) -> Option<SegmentIntersection> {
+        let [a, b] = self.0;
+        let [c, d] = other.0;
+        let (o1, o2) = (orient2d(a, b, c), orient2d(a, b, d));
+        let (o3, o4) = (orient2d(c, d, a), orient2d(c, d, b));
+        if [o1, o2, o3, o4]
+            .iter()
+            .all(|&o| o == Orientation::Collinear)
+        {
+            return self.collinear_overlap(other);
+        }
+        if o1 == o2 || o3 == o4 {
+            return None;
+        }
+
+        // Prefer endpoints that lie exactly on the other segment.
+        let pt = match (o1, o2, o3, o4) {
+            (Orientation::Collinear, ..) => c,
+            (_, Orientation::Collinear, ..) => d,
+            (.., Orientation::Collinear, _) => a,
+            (.., Orientation::Collinear) => b,
+            _ => {
+                let (dir, other_dir) = (b - a, d - c);
+                a + dir * ((c - a).perp_dot(other_dir) / dir.perp_dot(other_dir))
+            }
+        };
+        Some(SegmentIntersection::Point(pt))
+    }
+
+    fn collinear_overlap(&self, other: &Segment2) -> Option<SegmentIntersection> {
+        let [a, b] = self.0;
+        let [c, d] = other.0;
+        // Parameterise along the longer segment, which only has zero length if
+        // both are points.
+        let axis = if (b - a).length_squared() >= (d - c).length_squared() {
+            b - a
+        } else {
+            d - c
+        };
+        if axis == Vec2::ZERO {
+            return (a == c).then_some(SegmentIntersection::Point(a));
+        }
+        let order = |p: Vec2, q: Vec2| {
+            if p.dot(axis) <= q.dot(axis) {
+                (p, q)
+            } else {
+                (q, p)
+            }
+        };
+        let ((a_min, a_max), (c_min, c_max)) = (order(a, b), order(c, d));
+        let start = if a_min.dot(axis) >= c_min.dot(axis) {
+            a_min
+        } else {
+            c_min
+        };
+        let end = if a_max.dot(axis) <= c_max.dot(axis) {
+            a_max
+        } else {
+            c_max
+        };
+        match start.dot(axis).partial_cmp(&end.dot(axis))? {
+            core::cmp::Ordering::Greater => None,
+            core::cmp::Ordering::Equal => Some(SegmentIntersection::Point(start)),
+            core::cmp::Ordering::Less => Some(SegmentIntersection::Overlap(Segment2([start, end]))),
+        }
+    }
+
+    /// The distance along the ray, in multiples of `direction`, where it
+    /// crosses the segment. Rays running along the segment don't hit it.
+    pub fn raycast(&self, origin: Vec2, direction: Vec2, max_distance: f32) -> Option<f32> {
+        let [v1, v2] = self.0;
+        let edge = v2 - v1;
+        let den = direction.perp_dot(edge);
+        if den == 0.0 {
+            return None;
+        }
+        let offset = v1 - origin;
+        let distance = offset.perp_dot(edge) / den;
+        let along = offset.perp_dot(direction) / den;
+        ((0.0..=max_distance).contains(&distance) && (0.0..=1.0).contains(&along))
+            .then_some(distance)
+    }
+}
+
+/// The first of `edges` hit by the ray, for shapes that have already checked
+/// whether `origin` is inside them.
+pub(crate) fn raycast_edges(
+    edges: &[Segment2],
+    origin: Vec2,
+    direction: Vec2,
+    max_distance: f32,
+) -> Option<RayHit> {
+    let (edge, distance) = edges
+        .iter()
+        .filter_map(|edge| Some((edge, edge.raycast(origin, direction, max_distance)?)))
+        .min_by(|a, b| a.1.total_cmp(&b.1))?;
+    let normal = edge.direction().perp().normalize_or_zero();
+    Some(RayHit {
+        distance,
+        point: origin + direction * distance,
+        normal: if normal.dot(direction) > 0.0 {
+            -normal
+        } else {
+            normal
+        },
+    })
+}
+
+impl Index<usize> for Segment2 {
+    type Output = Vec2;
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.0[index]
+    }
+}
+impl IndexMut<usize> for Segment2 {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        &mut self.0[index]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(a: (f32, f32), b: (f32, f32)) -> Segment2 {
+        Segment2([Vec2::new(a.0, a.1), Vec2::new(b.0, b.1)])
+    }
+
+    #[test]
+    fn test_intersection() {
+        let sut = segment((0.0, 0.0), (4.0, 4.0));
+        assert_eq!(
+            sut.intersection(&segment((0.0, 4.0), (4.0, 0.0))),
+            Some(SegmentIntersection::Point(Vec2::splat(2.0)))
+        );
+        assert_eq!(
+            sut.intersection(&segment((1.0, 1.0), (3.0, -5.0))),
+            Some(SegmentIntersection::Point(Vec2::ONE))
+        );
+        assert_eq!(sut.intersection(&segment((0.0, 1.0), (3.0, 4.0))), None);
+        assert_eq!(sut.intersection(&segment((3.0, 0.0), (5.0, -2.0))), None);
+
+        assert_eq!(
+            sut.intersection(&segment((6.0, 6.0), (2.0, 2.0))),
+            Some(SegmentIntersection::Overlap(segment(
+                (2.0, 2.0),
+                (4.0, 4.0)
+            )))
+        );
+        assert_eq!(
+            sut.intersection(&segment((4.0, 4.0), (5.0, 5.0))),
+            Some(SegmentIntersection::Point(Vec2::splat(4.0)))
+        );
+        assert_eq!(sut.intersection(&segment((5.0, 5.0), (6.0, 6.0))), None);
+        assert_eq!(
+            sut.intersection(&segment((3.0, 3.0), (3.0, 3.0))),
+            Some(SegmentIntersection::Point(Vec2::splat(3.0)))
+        );
+        assert_eq!(segment((3.0, 0.0), (3.0, 0.0)).intersection(&sut), None);
+    }
+
+    #[test]
+    fn test_raycast() {
+        let sut = segment((2.0, -1.0), (2.0, 1.0));
+        assert_eq!(sut.raycast(Vec2::ZERO, Vec2::X * 0.5, 10.0), Some(4.0));
+        assert_eq!(sut.raycast(Vec2::ZERO, Vec2::X * 0.5, 3.0), None);
+        assert_eq!(sut.raycast(Vec2::ZERO, Vec2::NEG_X, 10.0), None);
+        assert_eq!(sut.raycast(Vec2::new(2.0, -5.0), Vec2::Y, 10.0), None);
+    }
+}
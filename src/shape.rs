@@ -0,0 +1,354 @@
+use alloc::boxed::Box;
+use glam::{Affine2, Vec2};
+
+use crate::{
+    aabb::Aabb2, circle::Circle, convex_polygon::ConvexPolygon2, polygon::Polygon2,
+    predicates::on_segment, quadrilateral::Quad2, segment::Segment2, triangle::Triangle2,
+};
+
+/// Common queries across 2D shapes, so they can be stored together as
+/// `dyn Shape2`.
+pub trait Shape2 {
+    /// Points on the boundary count as inside.
+    fn contains(&self, pt: Vec2) -> bool;
+    fn aabb(&self) -> Aabb2;
+    fn area(&self) -> f32;
+    /// The point of the shape furthest along `direction`, as used by GJK.
+    /// Concave shapes report the support of their convex hull.
+    fn support(&self, direction: Vec2) -> Vec2;
+    /// Shapes that can't represent the transformed result exactly, such as
+    /// circles under non-uniform scale, return a shape that bounds it. `None`
+    /// if the result isn't a valid shape of this type, such as a polygon
+    /// collapsed by a zero scale.
+    fn transform(&self, transform: &Affine2) -> Option<Self>
+    where
+        Self: Sized;
+    /// `transform` for shapes behind a `dyn Shape2`, keeping their type.
+    fn transformed(&self, transform: &Affine2) -> Option<Box<dyn Shape2>>;
+
+    #[cfg(feature = "macroquad-render")]
+    fn draw(&self, color: macroquad::prelude::Color);
+}
+
+impl Shape2 for Triangle2 {
+    fn contains(&self, pt: Vec2) -> bool {
+        Triangle2::contains(self, pt)
+    }
+    fn aabb(&self) -> Aabb2 {
+        Triangle2::aabb(self)
+    }
+    fn area(&self) -> f32 {
+        Triangle2::area(self)
+    }
+    fn support(&self, direction: Vec2) -> Vec2 {
+        crate::convex_polygon::support_of(&self.0, direction)
+    }
+    fn transform(&self, transform: &Affine2) -> Option<Self> {
+        Some(Triangle2::transform(self, transform))
+    }
+    fn transformed(&self, transform: &Affine2) -> Option<Box<dyn Shape2>> {
+        boxed(Shape2::transform(self, transform))
+    }
+
+    #[cfg(feature = "macroquad-render")]
+    fn draw(&self, color: macroquad::prelude::Color) {
+        Triangle2::draw(self, color);
+    }
+}
+
+impl Shape2 for Quad2 {
+    fn contains(&self, pt: Vec2) -> bool {
+        Quad2::contains(self, pt)
+    }
+    fn aabb(&self) -> Aabb2 {
+        Quad2::aabb(self)
+    }
+    fn area(&self) -> f32 {
+        Quad2::area(self)
+    }
+    fn support(&self, direction: Vec2) -> Vec2 {
+        crate::convex_polygon::support_of(&self.0, direction)
+    }
+    fn transform(&self, transform: &Affine2) -> Option<Self> {
+        Some(Quad2::transform(self, transform))
+    }
+    fn transformed(&self, transform: &Affine2) -> Option<Box<dyn Shape2>> {
+        boxed(Shape2::transform(self, transform))
+    }
+
+    #[cfg(feature = "macroquad-render")]
+    fn draw(&self, color: macroquad::prelude::Color) {
+        Quad2::draw(self, color);
+    }
+}
+
+impl Shape2 for Circle {
+    fn contains(&self, pt: Vec2) -> bool {
+        Circle::contains(self, pt)
+    }
+    fn aabb(&self) -> Aabb2 {
+        Circle::aabb(self)
+    }
+    fn area(&self) -> f32 {
+        core::f32::consts::PI * self.radius * self.radius
+    }
+    fn support(&self, direction: Vec2) -> Vec2 {
+        self.center + direction.normalize_or_zero() * self.radius
+    }
+    fn transform(&self, transform: &Affine2) -> Option<Self> {
+        let scale = transform
+            .matrix2
+            .x_axis
+            .length()
+            .max(transform.matrix2.y_axis.length());
+        Some(Circle::new(
+            transform.transform_point2(self.center),
+            self.radius * scale,
+        ))
+    }
+    fn transformed(&self, transform: &Affine2) -> Option<Box<dyn Shape2>> {
+        boxed(Shape2::transform(self, transform))
+    }
+
+    #[cfg(feature = "macroquad-render")]
+    fn draw(&self, color: macroquad::prelude::Color) {
+        macroquad::shapes::draw_circle(self.center.x, self.center.y, self.radius, color);
+    }
+}
+
+impl Shape2 for Aabb2 {
+    /// Unlike the half-open `Aabb2::contains`, so every shape agrees on its
+    /// boundary.
+    fn contains(&self, pt: Vec2) -> bool {
+        Aabb2::contains_inclusive(self, pt)
+    }
+    fn aabb(&self) -> Aabb2 {
+        *self
+    }
+    fn area(&self) -> f32 {
+        let size = self.size();
+        size.x * size.y
+    }
+    fn support(&self, direction: Vec2) -> Vec2 {
+        Vec2::select(direction.cmpge(Vec2::ZERO), self.max, self.min)
+    }
+    fn transform(&self, transform: &Affine2) -> Option<Self> {
+        let corners = [
+            self.min,
+            Vec2::new(self.max.x, self.min.y),
+            self.max,
+            Vec2::new(self.min.x, self.max.y),
+        ];
+        Some(Aabb2::from_points(
+            corners.map(|v| transform.transform_point2(v)),
+        ))
+    }
+    fn transformed(&self, transform: &Affine2) -> Option<Box<dyn Shape2>> {
+        boxed(Shape2::transform(self, transform))
+    }
+
+    #[cfg(feature = "macroquad-render")]
+    fn draw(&self, color: macroquad::prelude::Color) {
+        let size = self.size();
+        macroquad::shapes::draw_rectangle(self.min.x, self.min.y, size.x, size.y, color);
+    }
+}
+
+impl Shape2 for Segment2 {
+    fn contains(&self, pt: Vec2) -> bool {
+        let [v1, v2] = self.0;
+        on_segment(pt, v1, v2)
+    }
+    fn aabb(&self) -> Aabb2 {
+        Aabb2::from_points(self.0)
+    }
+    fn area(&self) -> f32 {
+        0.0
+    }
+    fn support(&self, direction: Vec2) -> Vec2 {
+        crate::convex_polygon::support_of(&self.0, direction)
+    }
+    fn transform(&self, transform: &Affine2) -> Option<Self> {
+        Some(Segment2(self.0.map(|v| transform.transform_point2(v))))
+    }
+    fn transformed(&self, transform: &Affine2) -> Option<Box<dyn Shape2>> {
+        boxed(Shape2::transform(self, transform))
+    }
+
+    #[cfg(feature = "macroquad-render")]
+    fn draw(&self, color: macroquad::prelude::Color) {
+        let [v1, v2] = self.0;
+        macroquad::shapes::draw_line(v1.x, v1.y, v2.x, v2.y, 1.0, color);
+    }
+}
+
+impl Shape2 for ConvexPolygon2 {
+    fn contains(&self, pt: Vec2) -> bool {
+        ConvexPolygon2::contains(self, pt)
+    }
+    fn aabb(&self) -> Aabb2 {
+        ConvexPolygon2::aabb(self)
+    }
+    fn area(&self) -> f32 {
+        ConvexPolygon2::area(self)
+    }
+    fn support(&self, direction: Vec2) -> Vec2 {
+        ConvexPolygon2::support(self, direction)
+    }
+    fn transform(&self, transform: &Affine2) -> Option<Self> {
+        let vertices = self
+            .vertices()
+            .iter()
+            .map(|v| transform.transform_point2(*v))
+            .collect();
+        // Mirroring flips the winding, which `new` corrects.
+        ConvexPolygon2::new(vertices)
+    }
+    fn transformed(&self, transform: &Affine2) -> Option<Box<dyn Shape2>> {
+        boxed(Shape2::transform(self, transform))
+    }
+
+    #[cfg(feature = "macroquad-render")]
+    fn draw(&self, color: macroquad::prelude::Color) {
+        let vertices = self.vertices();
+        for pair in vertices[1..].windows(2) {
+            macroquad::shapes::draw_triangle(vertices[0], pair[0], pair[1], color);
+        }
+    }
+}
+
+impl Shape2 for Polygon2 {
+    fn contains(&self, pt: Vec2) -> bool {
+        Polygon2::contains(self, pt)
+    }
+    fn aabb(&self) -> Aabb2 {
+        Polygon2::aabb(self)
+    }
+    fn area(&self) -> f32 {
+        Polygon2::area(self)
+    }
+    fn support(&self, direction: Vec2) -> Vec2 {
+        crate::convex_polygon::support_of(self.outline(), direction)
+    }
+    fn transform(&self, transform: &Affine2) -> Option<Self> {
+        let map = |ring: &[Vec2]| {
+            ring.iter()
+                .map(|v| transform.transform_point2(*v))
+                .collect()
+        };
+        let holes = self.holes().iter().map(|hole| map(hole)).collect();
+        Polygon2::with_holes(map(self.outline()), holes)
+    }
+    fn transformed(&self, transform: &Affine2) -> Option<Box<dyn Shape2>> {
+        boxed(Shape2::transform(self, transform))
+    }
+
+    #[cfg(feature = "macroquad-render")]
+    fn draw(&self, color: macroquad::prelude::Color) {
+        for triangle in self.triangulate().unwrap_or_default() {
+            triangle.draw(color);
+        }
+    }
+}
+
+fn boxed(shape: Option<impl Shape2 + 'static>) -> Option<Box<dyn Shape2>> {
+    shape.map(|shape| Box::new(shape) as Box<dyn Shape2>)
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{boxed::Box, vec, vec::Vec};
+
+    use super::*;
+
+    #[test]
+    fn test_heterogeneous_shapes() {
+        let shapes: Vec<Box<dyn Shape2>> = vec![
+            Box::new(Triangle2([
+                Vec2::ZERO,
+                Vec2::new(2.0, 0.0),
+                Vec2::new(0.0, 2.0),
+            ])),
+            Box::new(Quad2([Vec2::ZERO, Vec2::X, Vec2::ONE, Vec2::Y])),
+            Box::new(Circle::new(Vec2::ONE, 1.0)),
+            Box::new(Aabb2::new(Vec2::ZERO, Vec2::ONE)),
+            Box::new(Segment2([Vec2::ZERO, Vec2::ONE])),
+            Box::new(
+                ConvexPolygon2::convex_hull([Vec2::ZERO, Vec2::X, Vec2::ONE, Vec2::Y]).unwrap(),
+            ),
+        ];
+        for shape in &shapes {
+            assert!(shape.contains(Vec2::splat(0.5)));
+            assert!(!shape.contains(Vec2::splat(-0.5)));
+            let aabb = shape.aabb();
+            for direction in [Vec2::X, Vec2::NEG_Y, Vec2::new(-1.0, 2.0)] {
+                let support = shape.support(direction);
+                assert!(aabb.min.cmple(support).all() && support.cmple(aabb.max).all());
+            }
+        }
+        // Boundaries are inside for every shape, including boxes.
+        for i in [0, 1, 3, 4, 5] {
+            assert!(shapes[i].contains(Vec2::ZERO));
+        }
+        assert!(shapes[2].contains(Vec2::new(1.0, 0.0)));
+        let aabb = Aabb2::new(Vec2::ZERO, Vec2::ONE);
+        assert!(Shape2::contains(&aabb, Vec2::ONE));
+        assert!(!aabb.contains(Vec2::ONE));
+        assert!(aabb.contains_inclusive(Vec2::ONE));
+
+        let areas: Vec<f32> = shapes.iter().map(|shape| shape.area()).collect();
+        assert_eq!(areas, [2.0, 1.0, core::f32::consts::PI, 1.0, 0.0, 1.0]);
+    }
+
+    #[test]
+    fn test_transform() {
+        let transform = Affine2::from_scale_angle_translation(
+            Vec2::new(2.0, -1.0),
+            core::f32::consts::FRAC_PI_2,
+            Vec2::new(10.0, 0.0),
+        );
+        let circle = Circle::new(Vec2::ZERO, 1.0).transform(&transform).unwrap();
+        assert_eq!(circle, Circle::new(Vec2::new(10.0, 0.0), 2.0));
+
+        let aabb = Aabb2::new(Vec2::ZERO, Vec2::ONE)
+            .transform(&transform)
+            .unwrap();
+        assert!(aabb.min.abs_diff_eq(Vec2::new(10.0, 0.0), 1e-5));
+        assert!(aabb.max.abs_diff_eq(Vec2::new(11.0, 2.0), 1e-5));
+
+        let square =
+            ConvexPolygon2::convex_hull([Vec2::ZERO, Vec2::X, Vec2::ONE, Vec2::Y]).unwrap();
+        let mirrored = square.transform(&transform).unwrap();
+        assert!((mirrored.area() - 2.0).abs() < 1e-5);
+        assert!(mirrored.contains(Vec2::new(10.5, 1.0)));
+
+        let flatten = Affine2::from_scale(Vec2::new(1.0, 0.0));
+        assert_eq!(square.transform(&flatten), None);
+
+        let polygon = Polygon2::with_holes(
+            vec![
+                Vec2::ZERO,
+                Vec2::new(4.0, 0.0),
+                Vec2::splat(4.0),
+                Vec2::new(0.0, 4.0),
+            ],
+            vec![vec![Vec2::ONE, Vec2::new(2.0, 1.0), Vec2::splat(2.0)]],
+        )
+        .unwrap();
+        let moved = polygon.transform(&transform).unwrap();
+        assert!((moved.area() - 2.0 * polygon.area()).abs() < 1e-4);
+        assert!(moved.holes().iter().all(|hole| hole.len() == 3));
+        assert_eq!(polygon.transform(&flatten), None);
+
+        let shapes: Vec<Box<dyn Shape2>> = vec![Box::new(square), Box::new(polygon)];
+        let moved: Vec<_> = shapes
+            .iter()
+            .map(|shape| shape.transformed(&transform).unwrap())
+            .collect();
+        assert!(moved[0].contains(Vec2::new(10.5, 1.0)));
+        assert!((moved[1].area() - 2.0 * shapes[1].area()).abs() < 1e-4);
+        assert!(shapes
+            .iter()
+            .all(|shape| shape.transformed(&flatten).is_none()));
+    }
+}
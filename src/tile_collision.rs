@@ -0,0 +1,214 @@
+use glam::Vec2;
+use libm::{ceilf, floorf};
+
+use crate::{aabb::Aabb2, bitset2d::BitSet2d};
+
+/// Kinematic collision of axis-aligned boxes against a grid of solid tiles.
+///
+/// Tile `(x, y)` covers `[x, x + 1) * tile_size` by `[y, y + 1) * tile_size`,
+/// with `y` increasing downwards to match screen space.
+pub struct TileCollider<'a> {
+    solid: &'a BitSet2d,
+    tile_size: Vec2,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MoveAndSlide {
+    pub aabb: Aabb2,
+    /// The requested velocity, with any blocked axes zeroed.
+    pub velocity: Vec2,
+    pub x_normal: Option<Vec2>,
+    pub y_normal: Option<Vec2>,
+}
+impl MoveAndSlide {
+    pub fn position(&self) -> Vec2 {
+        self.aabb.min
+    }
+    pub fn normals(&self) -> impl Iterator<Item = Vec2> {
+        self.x_normal.into_iter().chain(self.y_normal)
+    }
+    pub fn is_grounded(&self) -> bool {
+        self.y_normal == Some(Vec2::NEG_Y)
+    }
+    pub fn is_on_ceiling(&self) -> bool {
+        self.y_normal == Some(Vec2::Y)
+    }
+    pub fn is_on_wall(&self) -> bool {
+        self.x_normal.is_some()
+    }
+}
+
+/// How far into a tile, as a fraction of its size, a box can be while still
+/// counting as only touching it.
+const TOLERANCE: f32 = 1e-4;
+
+impl<'a> TileCollider<'a> {
+    pub fn new(solid: &'a BitSet2d, tile_size: Vec2) -> Self {
+        Self { solid, tile_size }
+    }
+
+    /// Moves `aabb` by `velocity` (the displacement for this step), stopping
+    /// at the first solid tile along each axis and sliding along the other.
+    ///
+    /// Every tile between the start and end positions is tested, so large
+    /// velocities cannot tunnel through thin walls.
+    pub fn move_and_slide(&self, aabb: Aabb2, velocity: Vec2) -> MoveAndSlide {
+        let size = aabb.size();
+        let Aabb2 { mut min, mut max } = aabb;
+
+        let mut result = MoveAndSlide {
+            aabb,
+            velocity,
+            x_normal: None,
+            y_normal: None,
+        };
+        for axis in 0..2 {
+            let delta = velocity[axis];
+            match self.sweep_axis(min, max, delta, axis) {
+                Some(face) => {
+                    let normal = if delta > 0.0 { -1.0 } else { 1.0 };
+                    result.velocity[axis] = 0.0;
+                    if axis == 0 {
+                        result.x_normal = Some(Vec2::new(normal, 0.0));
+                    } else {
+                        result.y_normal = Some(Vec2::new(0.0, normal));
+                    }
+                    // Exactly on the face, so the next sweep starts from it.
+                    if delta > 0.0 {
+                        (min[axis], max[axis]) = (face - size[axis], face);
+                    } else {
+                        (min[axis], max[axis]) = (face, face + size[axis]);
+                    }
+                }
+                None => {
+                    min[axis] += delta;
+                    max[axis] = min[axis] + size[axis];
+                }
+            }
+        }
+
+        result.aabb = Aabb2::new(min, max);
+        result
+    }
+
+    /// Returns the face of the first solid tile hit along `axis`, in world
+    /// units.
+    fn sweep_axis(&self, min: Vec2, max: Vec2, delta: f32, axis: usize) -> Option<f32> {
+        let tile = self.tile_size[axis];
+        let other = 1 - axis;
+        // Boxes left touching a tile can end up a rounding error inside it, so
+        // edges within `TOLERANCE` of a tile boundary count as on it.
+        let first = |world: f32, tile_size: f32| floorf(world / tile_size + TOLERANCE) as isize;
+        let last = |world: f32, tile_size: f32| ceilf(world / tile_size - TOLERANCE) as isize;
+        let lanes =
+            first(min[other], self.tile_size[other])..last(max[other], self.tile_size[other]);
+
+        if delta > 0.0 {
+            (last(max[axis], tile)..last(max[axis] + delta, tile))
+                .find(|&i| self.is_blocked(i, lanes.clone(), axis))
+                .map(|i| i as f32 * tile)
+        } else if delta < 0.0 {
+            (first(min[axis] + delta, tile)..first(min[axis], tile))
+                .rev()
+                .find(|&i| self.is_blocked(i, lanes.clone(), axis))
+                .map(|i| (i + 1) as f32 * tile)
+        } else {
+            None
+        }
+    }
+
+    fn is_blocked(&self, i: isize, mut lanes: core::ops::Range<isize>, axis: usize) -> bool {
+        lanes.any(|j| self.solid.contains(if axis == 0 { (i, j) } else { (j, i) }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn floor(y: isize, xs: core::ops::Range<isize>) -> BitSet2d {
+        let mut tiles = BitSet2d::new();
+        for x in xs {
+            tiles.insert((x, y));
+        }
+        tiles
+    }
+
+    #[test]
+    fn test_land_on_floor() {
+        let tiles = floor(4, -10..10);
+        let sut = TileCollider::new(&tiles, Vec2::ONE);
+        let aabb = Aabb2::new(Vec2::new(0.25, 2.5), Vec2::new(0.75, 3.5));
+
+        let result = sut.move_and_slide(aabb, Vec2::new(0.0, 1.0));
+        assert!(result.is_grounded());
+        assert_eq!(result.aabb.max.y, 4.0);
+        assert_eq!(result.velocity, Vec2::ZERO);
+
+        let result = sut.move_and_slide(aabb, Vec2::new(0.0, 0.25));
+        assert!(!result.is_grounded());
+        assert_eq!(result.aabb.max.y, 3.75);
+    }
+
+    #[test]
+    fn test_no_tunnelling() {
+        let mut tiles = BitSet2d::new();
+        tiles.insert((5, 0));
+        let sut = TileCollider::new(&tiles, Vec2::splat(16.0));
+        let aabb = Aabb2::new(Vec2::new(0.0, 0.0), Vec2::new(8.0, 8.0));
+
+        let result = sut.move_and_slide(aabb, Vec2::new(1000.0, 0.0));
+        assert_eq!(result.aabb.max.x, 80.0);
+        assert_eq!(result.x_normal, Some(Vec2::NEG_X));
+
+        let result = sut.move_and_slide(
+            result.aabb.translate(Vec2::new(200.0, 0.0)),
+            Vec2::new(-1000.0, 0.0),
+        );
+        assert_eq!(result.aabb.min.x, 96.0);
+        assert_eq!(result.x_normal, Some(Vec2::X));
+    }
+
+    #[test]
+    fn test_slide_along_wall() {
+        let mut tiles = BitSet2d::new();
+        for y in -10..10 {
+            tiles.insert((2, y));
+        }
+        let sut = TileCollider::new(&tiles, Vec2::ONE);
+        let aabb = Aabb2::new(Vec2::new(0.0, 0.0), Vec2::new(1.0, 1.0));
+
+        let result = sut.move_and_slide(aabb, Vec2::new(3.0, -2.0));
+        assert_eq!(
+            result.aabb,
+            Aabb2::new(Vec2::new(1.0, -2.0), Vec2::new(2.0, -1.0))
+        );
+        assert_eq!(result.velocity, Vec2::new(0.0, -2.0));
+        assert!(result.is_on_wall());
+        assert!(!result.is_grounded());
+    }
+
+    #[test]
+    fn test_rest_on_floor_with_uneven_tiles() {
+        // Converting to tiles and back each step used to drift a resting box
+        // just past the floor's top, after which it fell through.
+        let tiles = floor(10, -10..10);
+        let tile_size = Vec2::splat(16.0 / 3.0);
+        let sut = TileCollider::new(&tiles, tile_size);
+        for (start, scale) in (0..20).flat_map(|start| [0.3, 0.9, 1.0].map(|s| (start, s))) {
+            let size = tile_size * scale;
+            let min = Vec2::new(1.0, start as f32 * 2.0);
+            let mut aabb = Aabb2::new(min, min + size);
+            let mut grounded = 0;
+            for _ in 0..1000 {
+                let result = sut.move_and_slide(aabb, Vec2::new(0.0, 0.29) * tile_size);
+                aabb = result.aabb;
+                assert!(aabb.max.y <= 10.0 * tile_size.y + 1e-4, "{start} {aabb:?}");
+                // Within a few rounding errors of the position, without building up.
+                assert!(aabb.size().abs_diff_eq(size, 2e-5), "{:?}", aabb.size());
+                grounded += usize::from(result.is_grounded());
+            }
+            assert!(grounded > 950, "{grounded}");
+        }
+    }
+}
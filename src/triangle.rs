@@ -1,18 +1,244 @@
-use core::ops::{Index, IndexMut};
+use core::ops::{Add, Index, IndexMut, Mul};
 
-use glam::Vec2;
+use glam::{Affine2, Mat3, Vec2, Vec3};
+use rand::Rng;
 
+use crate::{
+    aabb::Aabb2,
+    circle::Circle,
+    clipping::{clip_polygon, clip_polygon_to_aabb},
+    convex_polygon::ConvexPolygon2,
+    offset::Join,
+    predicates::{on_segment_of, orient2d, Orientation, Point},
+    quadrilateral::project,
+    raster::Spans,
+    segment::{raycast_edges, RayHit, Segment2},
+};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct Triangle2(pub [Vec2; 3]);
+
+/// How points exactly on an edge are treated by `Triangle2::contains_with`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Boundary {
+    #[default]
+    Inclusive,
+    Exclusive,
+    /// Each edge belongs to exactly one of the triangles sharing it, using the
+    /// top-left rule of GPU rasterisers (with `y` pointing down).
+    TopLeft,
+}
+
+/// `Triangle2::contains_with` for either precision.
+pub(crate) fn triangle_contains<P: Point>(vertices: [P; 3], pt: P, boundary: Boundary) -> bool {
+    let [v1, v2, v3] = vertices;
+    let winding = P::orient2d(v1, v2, v3);
+    if winding == Orientation::Collinear {
+        return boundary == Boundary::Inclusive
+            && (on_segment_of(pt, v1, v2)
+                || on_segment_of(pt, v2, v3)
+                || on_segment_of(pt, v3, v1));
+    }
+
+    [(v1, v2), (v2, v3), (v3, v1)].into_iter().all(|(a, b)| {
+        let side = P::orient2d(a, b, pt);
+        if side != Orientation::Collinear {
+            return side == winding;
+        }
+        match boundary {
+            Boundary::Inclusive => true,
+            Boundary::Exclusive => false,
+            Boundary::TopLeft => {
+                let edge = if winding == Orientation::Positive {
+                    b - a
+                } else {
+                    a - b
+                };
+                edge.is_top_left()
+            }
+        }
+    })
+}
+
 impl Triangle2 {
+    /// Points on the edges count as inside.
     pub fn contains(&self, pt: Vec2) -> bool {
+        self.contains_with(pt, Boundary::Inclusive)
+    }
+
+    /// Exact for any winding. Degenerate triangles have no interior, so only
+    /// `Boundary::Inclusive` accepts the points on their edges.
+    pub fn contains_with(&self, pt: Vec2, boundary: Boundary) -> bool {
+        triangle_contains(self.0, pt, boundary)
+    }
+
+    pub fn orientation(&self) -> Orientation {
+        let [v1, v2, v3] = self.0;
+        orient2d(v1, v2, v3)
+    }
+
+    /// Positive for `Orientation::Positive` windings.
+    pub fn signed_area(&self) -> f32 {
+        let [v1, v2, v3] = self.0;
+        0.5 * (v2 - v1).perp_dot(v3 - v1)
+    }
+    pub fn area(&self) -> f32 {
+        self.signed_area().abs()
+    }
+    pub fn centroid(&self) -> Vec2 {
+        let [v1, v2, v3] = self.0;
+        (v1 + v2 + v3) / 3.0
+    }
+    pub fn perimeter(&self) -> f32 {
+        self.edges().iter().map(Segment2::length).sum()
+    }
+    pub fn aabb(&self) -> Aabb2 {
+        Aabb2::from_points(self.0)
+    }
+
+    /// The edges, with edge `i` running from vertex `i` to vertex `i + 1`.
+    pub fn edges(&self) -> [Segment2; 3] {
+        let [v1, v2, v3] = self.0;
+        [Segment2([v1, v2]), Segment2([v2, v3]), Segment2([v3, v1])]
+    }
+    /// The part inside `clip`, or `None` if it has no area.
+    pub fn clip(&self, clip: &ConvexPolygon2) -> Option<ConvexPolygon2> {
+        ConvexPolygon2::convex_hull(clip_polygon(&self.0, clip))
+    }
+    pub fn clip_to_aabb(&self, aabb: &Aabb2) -> Option<ConvexPolygon2> {
+        ConvexPolygon2::convex_hull(clip_polygon_to_aabb(&self.0, aabb))
+    }
+
+    /// As `ConvexPolygon2::offset`, and also `None` if the triangle is
+    /// degenerate.
+    pub fn offset(&self, distance: f32, join: Join) -> Option<ConvexPolygon2> {
+        ConvexPolygon2::new(self.0.to_vec())?.offset(distance, join)
+    }
+
+    pub fn closest_point_on_boundary(&self, pt: Vec2) -> Vec2 {
+        closest_point_on_edges(&self.edges(), pt)
+    }
+
+    /// The first point along the ray inside the shape, where `distance` is in
+    /// multiples of `direction`.
+    pub fn raycast(&self, origin: Vec2, direction: Vec2, max_distance: f32) -> Option<RayHit> {
+        if self.contains(origin) {
+            return Some(RayHit {
+                distance: 0.0,
+                point: origin,
+                normal: Vec2::ZERO,
+            });
+        }
+        raycast_edges(&self.edges(), origin, direction, max_distance)
+    }
+    /// Casts from the segment's start to its end, so `distance` is in `0..=1`.
+    pub fn cast_segment(&self, segment: &Segment2) -> Option<RayHit> {
+        self.raycast(segment[0], segment.direction(), 1.0)
+    }
+
+    /// `None` if the triangle is degenerate.
+    pub fn circumcircle(&self) -> Option<Circle> {
+        let [v1, v2, v3] = self.0;
+        let (b, c) = (v2 - v1, v3 - v1);
+        let d = 2.0 * b.perp_dot(c);
+        if d == 0.0 {
+            return None;
+        }
+        let (b_sq, c_sq) = (b.length_squared(), c.length_squared());
+        let offset = Vec2::new(c.y * b_sq - b.y * c_sq, b.x * c_sq - c.x * b_sq) / d;
+        Some(Circle::new(v1 + offset, offset.length()))
+    }
+    /// `None` if the triangle is degenerate.
+    pub fn incircle(&self) -> Option<Circle> {
+        let [v1, v2, v3] = self.0;
+        // Each vertex is weighted by the length of the opposite edge.
+        let lengths = [v2.distance(v3), v3.distance(v1), v1.distance(v2)];
+        let perimeter: f32 = lengths.iter().sum();
+        let area = self.area();
+        if area == 0.0 || perimeter == 0.0 {
+            return None;
+        }
+        let center = (v1 * lengths[0] + v2 * lengths[1] + v3 * lengths[2]) / perimeter;
+        Some(Circle::new(center, 2.0 * area / perimeter))
+    }
+
+    /// Weights of each vertex such that they sum to one and blend the vertices
+    /// into `pt`, or `None` if the triangle is degenerate.
+    pub fn barycentric(&self, pt: Vec2) -> Option<Vec3> {
+        let [v1, v2, v3] = self.0;
+        let (e1, e2, r) = (v2 - v1, v3 - v1, pt - v1);
+        let denom = e1.perp_dot(e2);
+        if denom == 0.0 {
+            return None;
+        }
+        let w2 = r.perp_dot(e2) / denom;
+        let w3 = e1.perp_dot(r) / denom;
+        Some(Vec3::new(1.0 - w2 - w3, w2, w3))
+    }
+
+    pub fn from_barycentric(&self, weights: Vec3) -> Vec2 {
+        interpolate(weights, self.0)
+    }
+
+    /// Blends per-vertex attributes, such as colours or UVs, at `pt`.
+    pub fn interpolate<T>(&self, pt: Vec2, attributes: [T; 3]) -> Option<T>
+    where
+        T: Mul<f32, Output = T> + Add<Output = T>,
+    {
+        Some(interpolate(self.barycentric(pt)?, attributes))
+    }
+
+    /// A uniformly distributed point inside, by folding the far half of the
+    /// parallelogram spanned by two edges back onto the triangle.
+    pub fn sample(&self, rng: &mut impl Rng) -> Vec2 {
         let [v1, v2, v3] = self.0;
+        let (mut u, mut v): (f32, f32) = (rng.gen(), rng.gen());
+        if u + v > 1.0 {
+            (u, v) = (1.0 - u, 1.0 - v);
+        }
+        v1 + (v2 - v1) * u + (v3 - v1) * v
+    }
 
-        let d1 = (pt - v2).perp_dot(v1 - v2);
-        let d2 = (pt - v3).perp_dot(v2 - v3);
-        let d3 = (pt - v1).perp_dot(v3 - v1);
+    /// The pixel rows covered under the top-left rule.
+    pub fn spans(&self) -> Spans {
+        Spans::new([Some(*self), None], self.aabb())
+    }
 
-        return d1.signum() == d2.signum() && d1.signum() == d3.signum();
+    pub fn transform(&self, transform: &Affine2) -> Self {
+        Triangle2(self.0.map(|v| transform.transform_point2(v)))
     }
+    /// Applies a homogeneous 2D transform, which may include perspective, or
+    /// `None` if a vertex maps to infinity.
+    pub fn transform_mat3(&self, matrix: &Mat3) -> Option<Self> {
+        let [v1, v2, v3] = self.0.map(|v| project(*matrix, v));
+        Some(Triangle2([v1?, v2?, v3?]))
+    }
+    pub fn translate(&self, offset: Vec2) -> Self {
+        Triangle2(self.0.map(|v| v + offset))
+    }
+    /// Rotates by `angle` radians about `pivot`.
+    pub fn rotate_about(&self, pivot: Vec2, angle: f32) -> Self {
+        let rotation = Vec2::from_angle(angle);
+        Triangle2(self.0.map(|v| pivot + rotation.rotate(v - pivot)))
+    }
+    pub fn scale_about(&self, pivot: Vec2, scale: Vec2) -> Self {
+        Triangle2(self.0.map(|v| pivot + (v - pivot) * scale))
+    }
+}
+
+pub(crate) fn closest_point_on_edges(edges: &[Segment2], pt: Vec2) -> Vec2 {
+    edges
+        .iter()
+        .map(|edge| edge.closest_point(pt))
+        .min_by(|a, b| a.distance_squared(pt).total_cmp(&b.distance_squared(pt)))
+        .unwrap_or(pt)
+}
+
+pub fn interpolate<T>(weights: Vec3, [a1, a2, a3]: [T; 3]) -> T
+where
+    T: Mul<f32, Output = T> + Add<Output = T>,
+{
+    a1 * weights.x + a2 * weights.y + a3 * weights.z
 }
 
 impl Index<usize> for Triangle2 {
@@ -33,8 +259,218 @@ impl Triangle2 {
         let [v1, v2, v3] = self.0;
         macroquad::shapes::draw_triangle(v1, v2, v3, color);
     }
+    /// Draws with per-vertex texture coordinates and tints.
+    pub fn draw_textured(
+        &self,
+        texture: macroquad::texture::Texture2D,
+        uvs: [Vec2; 3],
+        colors: [macroquad::prelude::Color; 3],
+    ) {
+        let mut batch = crate::mesh_batch::MeshBatch::new(Some(texture));
+        batch.push_triangle(self, uvs, colors);
+        batch.draw();
+    }
     pub fn draw_lines(&self, thickness: f32, color: macroquad::prelude::Color) {
         let [v1, v2, v3] = self.0;
         macroquad::shapes::draw_triangle_lines(v1, v2, v3, thickness, color);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use alloc::{vec, vec::Vec};
+
+    use super::*;
+
+    #[test]
+    fn test_contains_boundary() {
+        let sut = Triangle2([
+            Vec2::new(0.0, 0.0),
+            Vec2::new(4.0, 0.0),
+            Vec2::new(0.0, 4.0),
+        ]);
+        let edge = Vec2::new(2.0, 2.0);
+        let inside = Vec2::new(1.0, 1.0);
+        let outside = Vec2::new(-0.0, -1.0);
+        for boundary in [Boundary::Inclusive, Boundary::Exclusive, Boundary::TopLeft] {
+            assert!(sut.contains_with(inside, boundary));
+            assert!(!sut.contains_with(outside, boundary));
+        }
+        assert!(sut.contains_with(edge, Boundary::Inclusive));
+        assert!(!sut.contains_with(edge, Boundary::Exclusive));
+        assert!(sut.contains_with(Vec2::new(-0.0, 1.0), Boundary::Inclusive));
+        assert!(sut.contains_with(Vec2::new(0.0, 1.0), Boundary::Inclusive));
+    }
+
+    #[test]
+    fn test_degenerate() {
+        let sut = Triangle2([
+            Vec2::new(0.0, 0.0),
+            Vec2::new(1.0, 1.0),
+            Vec2::new(2.0, 2.0),
+        ]);
+        assert!(sut.contains(Vec2::new(1.5, 1.5)));
+        assert!(!sut.contains(Vec2::new(1.5, 1.0)));
+        assert!(!sut.contains_with(Vec2::new(1.5, 1.5), Boundary::Exclusive));
+        assert!(!sut.contains_with(Vec2::new(1.5, 1.5), Boundary::TopLeft));
+    }
+
+    #[test]
+    fn test_barycentric() {
+        let sut = Triangle2([
+            Vec2::new(1.0, 1.0),
+            Vec2::new(5.0, 1.0),
+            Vec2::new(1.0, 3.0),
+        ]);
+        assert_eq!(sut.barycentric(sut[0]), Some(Vec3::X));
+        assert_eq!(sut.barycentric(sut[1]), Some(Vec3::Y));
+        assert_eq!(sut.barycentric(sut[2]), Some(Vec3::Z));
+
+        let pt = Vec2::new(2.0, 1.5);
+        let weights = sut.barycentric(pt).unwrap();
+        assert!((weights - Vec3::new(0.5, 0.25, 0.25)).abs().max_element() < 1e-6);
+        assert!(sut.from_barycentric(weights).abs_diff_eq(pt, 1e-6));
+        assert_eq!(sut.interpolate(pt, [0.0, 4.0, 8.0]), Some(3.0));
+
+        let degenerate = Triangle2([Vec2::ZERO, Vec2::ONE, Vec2::splat(2.0)]);
+        assert_eq!(degenerate.barycentric(pt), None);
+    }
+
+    #[test]
+    fn test_measurements() {
+        let sut = Triangle2([
+            Vec2::new(0.0, 0.0),
+            Vec2::new(3.0, 0.0),
+            Vec2::new(0.0, 4.0),
+        ]);
+        assert_eq!(sut.signed_area(), 6.0);
+        assert_eq!(Triangle2([sut[1], sut[0], sut[2]]).signed_area(), -6.0);
+        assert_eq!(sut.area(), 6.0);
+        assert_eq!(sut.perimeter(), 12.0);
+        assert_eq!(sut.centroid(), Vec2::new(1.0, 4.0 / 3.0));
+        assert_eq!(sut.aabb(), Aabb2::new(Vec2::ZERO, Vec2::new(3.0, 4.0)));
+        assert_eq!(
+            sut.circumcircle(),
+            Some(Circle::new(Vec2::new(1.5, 2.0), 2.5))
+        );
+        assert_eq!(sut.incircle(), Some(Circle::new(Vec2::ONE, 1.0)));
+        assert_eq!(
+            sut.closest_point_on_boundary(Vec2::new(1.0, 0.5)),
+            Vec2::new(1.0, 0.0)
+        );
+        assert_eq!(
+            sut.closest_point_on_boundary(Vec2::new(-1.0, 5.0)),
+            Vec2::new(0.0, 4.0)
+        );
+    }
+
+    #[test]
+    fn test_top_left_owns_shared_edges_once() {
+        // A square fanned around its centre, with mixed windings.
+        let c = Vec2::new(2.0, 2.0);
+        let corners = [
+            Vec2::new(0.0, 0.0),
+            Vec2::new(4.0, 0.0),
+            Vec2::new(4.0, 4.0),
+            Vec2::new(0.0, 4.0),
+        ];
+        let mesh: Vec<_> = (0..4)
+            .map(|i| {
+                let (a, b) = (corners[i], corners[(i + 1) % 4]);
+                if i % 2 == 0 {
+                    Triangle2([a, b, c])
+                } else {
+                    Triangle2([b, a, c])
+                }
+            })
+            .collect();
+
+        for iy in 1..16 {
+            for ix in 1..16 {
+                let pt = Vec2::new(ix as f32, iy as f32) * 0.25;
+                let owners = mesh
+                    .iter()
+                    .filter(|t| t.contains_with(pt, Boundary::TopLeft))
+                    .count();
+                assert_eq!(owners, 1, "{pt:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_transforms() {
+        let sut = Triangle2([Vec2::ZERO, Vec2::X, Vec2::Y]);
+        let rotated = sut.rotate_about(Vec2::X, core::f32::consts::PI);
+        let expected = [Vec2::X * 2.0, Vec2::X, Vec2::new(2.0, -1.0)];
+        for (actual, expected) in rotated.0.iter().zip(expected) {
+            assert!(actual.abs_diff_eq(expected, 1e-6), "{rotated:?}");
+        }
+
+        let scaled = sut
+            .scale_about(Vec2::ONE, Vec2::splat(2.0))
+            .translate(Vec2::Y);
+        assert_eq!(
+            scaled,
+            Triangle2([
+                Vec2::new(-1.0, 0.0),
+                Vec2::new(1.0, 0.0),
+                Vec2::new(-1.0, 2.0)
+            ])
+        );
+        assert_eq!(scaled.area(), 4.0 * sut.area());
+
+        let perspective = Mat3::from_cols(Vec3::X, Vec3::Y, Vec3::new(0.0, 0.0, 0.0));
+        assert_eq!(sut.transform_mat3(&perspective), None);
+    }
+
+    #[test]
+    fn test_raycast() {
+        let sut = Triangle2([Vec2::ZERO, Vec2::new(4.0, 0.0), Vec2::new(0.0, 4.0)]);
+        let hit = sut.raycast(Vec2::new(-2.0, 1.0), Vec2::X, 10.0).unwrap();
+        assert_eq!(hit.distance, 2.0);
+        assert_eq!(hit.point, Vec2::new(0.0, 1.0));
+        assert_eq!(hit.normal, Vec2::NEG_X);
+
+        let hit = sut
+            .raycast(Vec2::new(4.0, 4.0), Vec2::splat(-0.5), 10.0)
+            .unwrap();
+        assert_eq!(hit.distance, 4.0);
+        assert!(hit.normal.abs_diff_eq(Vec2::ONE.normalize(), 1e-6));
+
+        assert_eq!(sut.raycast(Vec2::new(-2.0, 1.0), Vec2::X, 1.0), None);
+        assert_eq!(sut.raycast(Vec2::new(-2.0, 1.0), Vec2::NEG_X, 10.0), None);
+        assert_eq!(
+            sut.raycast(Vec2::ONE, Vec2::X, 10.0).unwrap().normal,
+            Vec2::ZERO
+        );
+
+        let segment = Segment2([Vec2::new(1.0, -2.0), Vec2::new(1.0, 2.0)]);
+        let hit = sut.cast_segment(&segment).unwrap();
+        assert_eq!((hit.distance, hit.normal), (0.5, Vec2::NEG_Y));
+    }
+
+    #[test]
+    fn test_clip() {
+        let sut = Triangle2([Vec2::ZERO, Vec2::new(4.0, 0.0), Vec2::new(0.0, 4.0)]);
+        let clipped = sut
+            .clip_to_aabb(&Aabb2::new(Vec2::ZERO, Vec2::splat(2.0)))
+            .unwrap();
+        assert!((clipped.area() - 4.0).abs() < 1e-5);
+        let clipped = sut
+            .clip_to_aabb(&Aabb2::new(Vec2::ONE, Vec2::splat(4.0)))
+            .unwrap();
+        assert_eq!(clipped.vertices().len(), 3);
+        assert!((clipped.area() - 2.0).abs() < 1e-5);
+        assert_eq!(
+            sut.clip_to_aabb(&Aabb2::new(Vec2::splat(3.0), Vec2::splat(4.0))),
+            None
+        );
+
+        let clip = ConvexPolygon2::new(vec![
+            Vec2::new(-1.0, 1.0),
+            Vec2::new(5.0, 1.0),
+            Vec2::new(-1.0, 7.0),
+        ]);
+        assert!((sut.clip(&clip.unwrap()).unwrap().area() - 4.5).abs() < 1e-5);
+    }
+}
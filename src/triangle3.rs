@@ -0,0 +1,243 @@
+use core::ops::{Index, IndexMut};
+
+use glam::Vec3;
+
+use crate::triangle::interpolate;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Triangle3(pub [Vec3; 3]);
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RayHit3 {
+    /// In multiples of the ray's direction.
+    pub distance: f32,
+    pub point: Vec3,
+    /// Weights of each vertex at `point`, for interpolating UVs and the like.
+    pub barycentric: Vec3,
+    /// Whether the ray hit the side `normal` points out of.
+    pub front_face: bool,
+}
+
+impl Triangle3 {
+    /// The unit normal, following the right-hand rule through `v1`, `v2`,
+    /// `v3`, or zero if the triangle is degenerate.
+    pub fn normal(&self) -> Vec3 {
+        self.scaled_normal().normalize_or_zero()
+    }
+    pub fn area(&self) -> f32 {
+        0.5 * self.scaled_normal().length()
+    }
+    pub fn centroid(&self) -> Vec3 {
+        let [v1, v2, v3] = self.0;
+        (v1 + v2 + v3) / 3.0
+    }
+
+    /// Weights for the projection of `pt` onto the triangle's plane, or
+    /// `None` if the triangle is degenerate.
+    pub fn barycentric(&self, pt: Vec3) -> Option<Vec3> {
+        let [v1, v2, v3] = self.0;
+        let (e1, e2, r) = (v2 - v1, v3 - v1, pt - v1);
+        let (d11, d12, d22) = (e1.dot(e1), e1.dot(e2), e2.dot(e2));
+        let (d1r, d2r) = (e1.dot(r), e2.dot(r));
+        let denom = d11 * d22 - d12 * d12;
+        if denom == 0.0 {
+            return None;
+        }
+        let w2 = (d22 * d1r - d12 * d2r) / denom;
+        let w3 = (d11 * d2r - d12 * d1r) / denom;
+        Some(Vec3::new(1.0 - w2 - w3, w2, w3))
+    }
+    pub fn from_barycentric(&self, weights: Vec3) -> Vec3 {
+        interpolate(weights, self.0)
+    }
+
+    /// The nearest point on or inside the triangle, by checking which vertex,
+    /// edge or face region `pt` projects into.
+    pub fn closest_point(&self, pt: Vec3) -> Vec3 {
+        let [a, b, c] = self.0;
+        let (ab, ac, ap) = (b - a, c - a, pt - a);
+        let (d1, d2) = (ab.dot(ap), ac.dot(ap));
+        if d1 <= 0.0 && d2 <= 0.0 {
+            return a;
+        }
+
+        let bp = pt - b;
+        let (d3, d4) = (ab.dot(bp), ac.dot(bp));
+        if d3 >= 0.0 && d4 <= d3 {
+            return b;
+        }
+        let vc = d1 * d4 - d3 * d2;
+        if vc <= 0.0 && d1 >= 0.0 && d3 <= 0.0 {
+            return a + ab * (d1 / (d1 - d3));
+        }
+
+        let cp = pt - c;
+        let (d5, d6) = (ab.dot(cp), ac.dot(cp));
+        if d6 >= 0.0 && d5 <= d6 {
+            return c;
+        }
+        let vb = d5 * d2 - d1 * d6;
+        if vb <= 0.0 && d2 >= 0.0 && d6 <= 0.0 {
+            return a + ac * (d2 / (d2 - d6));
+        }
+        let va = d3 * d6 - d5 * d4;
+        if va <= 0.0 && d4 - d3 >= 0.0 && d5 - d6 >= 0.0 {
+            return b + (c - b) * ((d4 - d3) / ((d4 - d3) + (d5 - d6)));
+        }
+
+        let denom = va + vb + vc;
+        a + ab * (vb / denom) + ac * (vc / denom)
+    }
+
+    /// Möller–Trumbore, hitting either face. `distance` is in multiples of
+    /// `direction`, and rays in the triangle's plane never hit.
+    pub fn raycast(&self, origin: Vec3, direction: Vec3, max_distance: f32) -> Option<RayHit3> {
+        let [v1, v2, v3] = self.0;
+        let (e1, e2) = (v2 - v1, v3 - v1);
+        let p = direction.cross(e2);
+        let det = e1.dot(p);
+        if det == 0.0 {
+            return None;
+        }
+        let inv_det = 1.0 / det;
+
+        let s = origin - v1;
+        let u = s.dot(p) * inv_det;
+        if !(0.0..=1.0).contains(&u) {
+            return None;
+        }
+        let q = s.cross(e1);
+        let v = direction.dot(q) * inv_det;
+        if v < 0.0 || u + v > 1.0 {
+            return None;
+        }
+        let distance = e2.dot(q) * inv_det;
+        if !(0.0..=max_distance).contains(&distance) {
+            return None;
+        }
+        Some(RayHit3 {
+            distance,
+            point: origin + direction * distance,
+            barycentric: Vec3::new(1.0 - u - v, u, v),
+            front_face: det > 0.0,
+        })
+    }
+
+    fn scaled_normal(&self) -> Vec3 {
+        let [v1, v2, v3] = self.0;
+        (v2 - v1).cross(v3 - v1)
+    }
+}
+
+impl Index<usize> for Triangle3 {
+    type Output = Vec3;
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.0[index]
+    }
+}
+impl IndexMut<usize> for Triangle3 {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        &mut self.0[index]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{Rng, SeedableRng};
+
+    use super::*;
+
+    fn sut() -> Triangle3 {
+        Triangle3([
+            Vec3::ZERO,
+            Vec3::new(2.0, 0.0, 0.0),
+            Vec3::new(0.0, 2.0, 0.0),
+        ])
+    }
+
+    #[test]
+    fn test_measurements() {
+        let sut = sut();
+        assert_eq!(sut.normal(), Vec3::Z);
+        assert_eq!(sut.area(), 2.0);
+        assert_eq!(
+            sut.barycentric(Vec3::new(1.0, 0.5, 3.0)),
+            Some(Vec3::new(0.25, 0.5, 0.25))
+        );
+        assert_eq!(
+            sut.from_barycentric(Vec3::new(0.25, 0.5, 0.25)),
+            Vec3::new(1.0, 0.5, 0.0)
+        );
+
+        let degenerate = Triangle3([Vec3::ZERO, Vec3::X, Vec3::X * 2.0]);
+        assert_eq!(degenerate.normal(), Vec3::ZERO);
+        assert_eq!(degenerate.barycentric(Vec3::ONE), None);
+    }
+
+    #[test]
+    fn test_closest_point() {
+        let sut = sut();
+        assert_eq!(
+            sut.closest_point(Vec3::new(0.5, 0.5, 4.0)),
+            Vec3::new(0.5, 0.5, 0.0)
+        );
+        assert_eq!(sut.closest_point(Vec3::new(-1.0, -1.0, 1.0)), Vec3::ZERO);
+        assert_eq!(sut.closest_point(Vec3::new(1.0, -3.0, 0.0)), Vec3::X);
+        assert_eq!(
+            sut.closest_point(Vec3::new(2.0, 2.0, -1.0)),
+            Vec3::new(1.0, 1.0, 0.0)
+        );
+
+        // No other point in the triangle is closer.
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+        let tilted = Triangle3([
+            Vec3::new(1.0, 0.0, 2.0),
+            Vec3::new(-1.0, 3.0, 0.0),
+            Vec3::new(0.0, -2.0, -1.0),
+        ]);
+        for _ in 0..100 {
+            let pt = Vec3::new(
+                rng.gen_range(-4.0..4.0),
+                rng.gen_range(-4.0..4.0),
+                rng.gen_range(-4.0..4.0),
+            );
+            let closest = tilted.closest_point(pt).distance(pt);
+            for _ in 0..50 {
+                let (mut u, mut v): (f32, f32) = (rng.gen(), rng.gen());
+                if u + v > 1.0 {
+                    (u, v) = (1.0 - u, 1.0 - v);
+                }
+                let other = tilted.from_barycentric(Vec3::new(1.0 - u - v, u, v));
+                assert!(closest <= other.distance(pt) + 1e-4);
+            }
+        }
+    }
+
+    #[test]
+    fn test_raycast() {
+        let sut = sut();
+        let hit = sut
+            .raycast(Vec3::new(0.5, 0.5, 5.0), Vec3::NEG_Z * 2.0, 10.0)
+            .unwrap();
+        assert_eq!(hit.distance, 2.5);
+        assert_eq!(hit.point, Vec3::new(0.5, 0.5, 0.0));
+        assert_eq!(hit.barycentric, Vec3::new(0.5, 0.25, 0.25));
+        assert!(hit.front_face);
+        assert!(
+            !sut.raycast(Vec3::new(0.5, 0.5, -5.0), Vec3::Z, 10.0)
+                .unwrap()
+                .front_face
+        );
+
+        assert_eq!(
+            sut.raycast(Vec3::new(0.5, 0.5, 5.0), Vec3::NEG_Z, 4.0),
+            None
+        );
+        assert_eq!(sut.raycast(Vec3::new(0.5, 0.5, 5.0), Vec3::Z, 10.0), None);
+        assert_eq!(
+            sut.raycast(Vec3::new(1.5, 1.5, 5.0), Vec3::NEG_Z, 10.0),
+            None
+        );
+        assert_eq!(sut.raycast(Vec3::new(-1.0, 0.5, 0.0), Vec3::X, 10.0), None);
+    }
+}